@@ -0,0 +1,311 @@
+use fxhash::FxHashSet;
+use regex::Regex;
+use std::sync;
+
+static CLASS_SELECTOR_REGEX: sync::LazyLock<Regex> =
+    sync::LazyLock::new(|| Regex::new(r"\.(-?[_a-zA-Z](?:[\w-]|\\.)*)").unwrap());
+
+// Same as `CLASS_SELECTOR_REGEX`, but with support for the hex escapes of generated CSS, e.g.:
+// `.\32 xl\:flex` for `2xl:flex`.
+static ESCAPED_CLASS_SELECTOR_REGEX: sync::LazyLock<Regex> = sync::LazyLock::new(|| {
+    Regex::new(
+        r"\.(-?(?:[_a-zA-Z]|\\[0-9a-fA-F]{1,6} ?|\\[^0-9a-fA-F])(?:[\w-]|\\[0-9a-fA-F]{1,6} ?|\\[^0-9a-fA-F])*)",
+    )
+    .unwrap()
+});
+
+/// Everything that is used or defined inside of the `@layer` blocks of a CSS file, see
+/// [extract_layer_candidates].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LayerCandidates {
+    /// Utilities used by `@apply`, e.g.: `px-4` in `.btn { @apply px-4; }`
+    pub utilities: Vec<String>,
+    /// Classes defined by the selectors, e.g.: `btn` in `.btn { @apply px-4; }`
+    pub classes: Vec<String>,
+}
+
+// The kind of block we are in while walking through the CSS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Block {
+    Layer,
+    Other,
+}
+
+/// Extract the `@apply` utilities and the custom classes that are defined inside of `@layer`
+/// blocks, e.g.: `@layer components { .btn { @apply px-4 py-2; } }`. Everything outside of
+/// `@layer` blocks is ignored. Both lists are sorted and unique.
+///
+/// CSS files are not scanned for candidates by default, so this has to be called explicitly,
+/// e.g.: to build a safelist from custom components.
+pub fn extract_layer_candidates(css: &str) -> LayerCandidates {
+    let mut result = LayerCandidates::default();
+
+    let mut stack: Vec<Block> = vec![];
+    let mut prelude = String::new();
+    let mut chars = css.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            // Comments
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+
+            // Strings can contain anything, e.g.: `content: "{"`
+            '"' | '\'' => {
+                prelude.push(c);
+                while let Some(next) = chars.next() {
+                    prelude.push(next);
+                    match next {
+                        '\\' => prelude.extend(chars.next()),
+                        _ if next == c => break,
+                        _ => {}
+                    }
+                }
+            }
+
+            '{' => {
+                let selector = prelude.trim();
+                let in_layer = stack.contains(&Block::Layer);
+
+                if selector.starts_with("@layer") {
+                    stack.push(Block::Layer);
+                } else {
+                    if in_layer && !selector.starts_with('@') {
+                        result.classes.extend(
+                            CLASS_SELECTOR_REGEX
+                                .captures_iter(selector)
+                                .map(|captures| captures[1].replace('\\', "")),
+                        );
+                    }
+
+                    stack.push(Block::Other);
+                }
+
+                prelude.clear();
+            }
+
+            '}' => {
+                stack.pop();
+                prelude.clear();
+            }
+
+            ';' => {
+                let declaration = prelude.trim();
+
+                if let Some(utilities) = declaration.strip_prefix("@apply") {
+                    if stack.contains(&Block::Layer) {
+                        result.utilities.extend(
+                            utilities
+                                .split_whitespace()
+                                .filter(|utility| *utility != "!important")
+                                .map(|utility| utility.to_owned()),
+                        );
+                    }
+                }
+
+                prelude.clear();
+            }
+
+            _ => prelude.push(c),
+        }
+    }
+
+    result.utilities.sort_unstable();
+    result.utilities.dedup();
+    result.classes.sort_unstable();
+    result.classes.dedup();
+
+    result
+}
+
+/// Extract the classes of all selectors in a CSS file, e.g.: `hover:bg-red-500` in
+/// `.hover\:bg-red-500:hover { ... }`. Escapes are resolved and at-rule preludes are ignored.
+///
+/// This can be used to build the baseline for [crate::Scanner::scan_against_baseline] from CSS
+/// that was generated before. Every class selector is returned, so classes that are only used by
+/// variants are part of the result as well, e.g.: `dark` in `.dark\:flex:where(.dark, .dark *)`.
+pub fn candidates_from_css(css: &str) -> FxHashSet<String> {
+    let mut result = FxHashSet::default();
+
+    let mut prelude = String::new();
+    let mut chars = css.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            // Comments
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+
+            // Strings are never part of a class, e.g.: `content: ".foo"`
+            '"' | '\'' => {
+                while let Some(next) = chars.next() {
+                    match next {
+                        '\\' => {
+                            chars.next();
+                        }
+                        _ if next == c => break,
+                        _ => {}
+                    }
+                }
+            }
+
+            // Escaped characters are part of the selector, e.g.: `.w-\[10px\]`
+            '\\' => {
+                prelude.push(c);
+                prelude.extend(chars.next());
+            }
+
+            '{' => {
+                let selector = prelude.trim();
+
+                if !selector.starts_with('@') {
+                    result.extend(
+                        ESCAPED_CLASS_SELECTOR_REGEX
+                            .captures_iter(selector)
+                            .map(|captures| unescape(&captures[1])),
+                    );
+                }
+
+                prelude.clear();
+            }
+
+            '}' | ';' => prelude.clear(),
+
+            _ => prelude.push(c),
+        }
+    }
+
+    result
+}
+
+// Resolve the escapes of a CSS identifier, e.g.: `\32 xl\:flex` becomes `2xl:flex`.
+fn unescape(ident: &str) -> String {
+    let mut result = String::with_capacity(ident.len());
+    let mut chars = ident.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        let mut hex = String::new();
+        while hex.len() < 6 && chars.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+            hex.extend(chars.next());
+        }
+
+        if hex.is_empty() {
+            result.extend(chars.next());
+            continue;
+        }
+
+        // A single whitespace character terminates a hex escape
+        chars.next_if_eq(&' ');
+
+        result.extend(u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{candidates_from_css, extract_layer_candidates, LayerCandidates};
+
+    #[test]
+    fn test_layer_candidates() {
+        let css = r#"
+            @import "tailwindcss";
+
+            .outside { @apply underline; }
+
+            @layer components {
+              /* .commented { @apply italic; } */
+              .btn {
+                @apply px-4 py-2;
+              }
+
+              .btn-primary:hover, .card > .card-title {
+                @apply bg-blue-500 hover:bg-blue-600 !important;
+                content: "{";
+              }
+
+              @media (width >= 40rem) {
+                .md\:btn { @apply px-6; }
+              }
+            }
+        "#;
+
+        assert_eq!(
+            extract_layer_candidates(css),
+            LayerCandidates {
+                utilities: vec![
+                    "bg-blue-500".to_owned(),
+                    "hover:bg-blue-600".to_owned(),
+                    "px-4".to_owned(),
+                    "px-6".to_owned(),
+                    "py-2".to_owned(),
+                ],
+                classes: vec![
+                    "btn".to_owned(),
+                    "btn-primary".to_owned(),
+                    "card".to_owned(),
+                    "card-title".to_owned(),
+                    "md:btn".to_owned(),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_candidates_from_css() {
+        let css = r#"
+            /*! tailwindcss v4.0.0 | MIT License | https://tailwindcss.com */
+            @layer utilities {
+              .flex { display: flex; }
+              .-mt-4 { margin-top: calc(var(--spacing) * -4); }
+              .w-\[10px\] { width: 10px; }
+              .hover\:bg-red-500:hover { background-color: var(--color-red-500); }
+              .content-\[\'a\'\] { content: ".not-a-class"; }
+              /* .commented { display: none; } */
+              @media (width >= 96rem) {
+                .\32 xl\:grid { display: grid; }
+              }
+              @keyframes spin {
+                12.5% { opacity: 0.5; }
+              }
+            }
+        "#;
+
+        let mut candidates: Vec<String> = candidates_from_css(css).into_iter().collect();
+        candidates.sort_unstable();
+
+        assert_eq!(
+            candidates,
+            vec![
+                "-mt-4",
+                "2xl:grid",
+                "content-['a']",
+                "flex",
+                "hover:bg-red-500",
+                "w-[10px]",
+            ]
+        );
+    }
+}
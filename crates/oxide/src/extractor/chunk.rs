@@ -0,0 +1,114 @@
+/// Split the content into chunks of roughly `target_chunk_size` bytes, that can be extracted
+/// independently.
+///
+/// Chunks are only split at newlines, or at whitespace that is not part of brackets or quotes.
+/// This ensures that no candidate is ever split across two chunks. Chunks can be larger than
+/// `target_chunk_size` when there is no safe boundary, and concatenating all chunks results in
+/// the original content.
+pub fn chunk_content(content: &[u8], target_chunk_size: usize) -> Vec<&[u8]> {
+    let mut chunks = vec![];
+    let mut chunk_start = 0;
+
+    let mut depth = 0usize;
+    let mut quote: Option<u8> = None;
+
+    for (idx, c) in content.iter().enumerate() {
+        let is_boundary = match (c, quote) {
+            // Brackets and quotes can't span multiple lines in practice, resetting them ensures
+            // that an unbalanced `(` or `'` (e.g.: in `can't`) doesn't prevent further splits.
+            (b'\n', _) => {
+                depth = 0;
+                quote = None;
+                true
+            }
+
+            // End of the current string
+            (x, Some(q)) if *x == q => {
+                quote = None;
+                false
+            }
+
+            // Everything inside of a string is part of that string
+            (_, Some(_)) => false,
+
+            (b'"' | b'\'' | b'`', None) => {
+                quote = Some(*c);
+                false
+            }
+
+            (b'(' | b'[' | b'{', None) => {
+                depth += 1;
+                false
+            }
+
+            (b')' | b']' | b'}', None) => {
+                depth = depth.saturating_sub(1);
+                false
+            }
+
+            (x, None) => depth == 0 && x.is_ascii_whitespace(),
+        };
+
+        if is_boundary && idx + 1 - chunk_start >= target_chunk_size {
+            chunks.push(&content[chunk_start..=idx]);
+            chunk_start = idx + 1;
+        }
+    }
+
+    if chunk_start < content.len() {
+        chunks.push(&content[chunk_start..]);
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::chunk_content;
+    use crate::extractor::{Extracted, Extractor};
+    use std::collections::BTreeSet;
+
+    fn extract(content: &[u8]) -> Vec<String> {
+        Extractor::new(content)
+            .extract()
+            .into_iter()
+            .filter_map(|x| match x {
+                Extracted::Candidate(bytes) => Some(String::from_utf8_lossy(bytes).to_string()),
+                Extracted::CssVariable(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_chunks_reassemble_to_the_original_content() {
+        let content = b"<div class=\"flex p-4\">\n  <p class='underline'>Hello</p>\n</div>";
+
+        for target_chunk_size in [0, 1, 4, 16, 1024] {
+            let chunks = chunk_content(content, target_chunk_size);
+            assert_eq!(chunks.concat(), content);
+        }
+
+        assert_eq!(chunk_content(b"", 16), Vec::<&[u8]>::new());
+    }
+
+    #[test]
+    fn test_chunks_do_not_split_candidates() {
+        let content = r#"<div class="flex content-['hello_world'] bg-[url(https://example.com/a_b.png)] grid-cols-[repeat(2,_minmax(0,_1fr))]">It's a test</div> <span class="underline [&_p]:mt-4"></span>"#
+            .repeat(8);
+        let content = content.as_bytes();
+
+        let expected = extract(content).into_iter().collect::<BTreeSet<_>>();
+
+        for target_chunk_size in [1, 8, 32, 100] {
+            let chunks = chunk_content(content, target_chunk_size);
+            assert!(chunks.len() > 1);
+
+            let actual = chunks
+                .iter()
+                .flat_map(|chunk| extract(chunk))
+                .collect::<BTreeSet<_>>();
+
+            assert_eq!(actual, expected);
+        }
+    }
+}
@@ -5,12 +5,15 @@ use css_variable_machine::CssVariableMachine;
 use machine::{Machine, MachineState};
 use std::fmt;
 
+pub use chunk::chunk_content;
+
 pub mod arbitrary_property_machine;
 pub mod arbitrary_value_machine;
 pub mod arbitrary_variable_machine;
 mod boundary;
 pub mod bracket_stack;
 pub mod candidate_machine;
+pub mod chunk;
 pub mod css_variable_machine;
 pub mod machine;
 pub mod modifier_machine;
@@ -962,6 +965,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_container_queries_and_nested_selector_variants() {
+        for (input, expected) in [
+            ("@md:flex", vec!["@md:flex"]),
+            ("@[480px]:grid", vec!["@[480px]:grid"]),
+            ("[&_.foo]:block", vec!["[&_.foo]:block"]),
+            (
+                r#"<div class="@md:flex @[480px]:grid [&_.foo]:block"></div>"#,
+                vec!["class", "@md:flex", "@[480px]:grid", "[&_.foo]:block"],
+            ),
+        ] {
+            assert_extract_sorted_candidates(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_arbitrary_variants_with_commas_and_combinators() {
+        for (input, expected) in [
+            (
+                "[&:is(:hover,:focus)]:flex",
+                vec!["[&:is(:hover,:focus)]:flex"],
+            ),
+            ("[&>*]:mt-2", vec!["[&>*]:mt-2"]),
+            ("[&_li+li]:border-t", vec!["[&_li+li]:border-t"]),
+            ("[&~p]:italic", vec!["[&~p]:italic"]),
+            (
+                "[&:not(:first-child,:last-child)]:px-2",
+                vec!["[&:not(:first-child,:last-child)]:px-2"],
+            ),
+            (
+                r#"<div class="[&:is(:hover,:focus)]:flex [&>*]:mt-2"></div>"#,
+                vec!["class", "[&:is(:hover,:focus)]:flex", "[&>*]:mt-2"],
+            ),
+        ] {
+            assert_extract_sorted_candidates(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_arbitrary_variants_with_underscores_for_whitespace() {
+        for (input, expected) in [
+            ("[&_p]:mt-4", vec!["[&_p]:mt-4"]),
+            ("[&:has(>.x)]:block", vec!["[&:has(>.x)]:block"]),
+            ("[&:has(>_.foo)]:flex", vec!["[&:has(>_.foo)]:flex"]),
+            // Whitespace ends a class in the browser as well, so this is never a single candidate.
+            // Whitespace inside of arbitrary variants has to be written as `_`.
+            ("[&:has(> .foo)]:flex", vec![]),
+        ] {
+            assert_extract_sorted_candidates(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_named_group_and_peer_variants() {
+        for (input, expected) in [
+            ("group/sidebar", vec!["group/sidebar"]),
+            ("peer/email", vec!["peer/email"]),
+            (
+                "group-hover/sidebar:bg-white",
+                vec!["group-hover/sidebar:bg-white"],
+            ),
+            (
+                "peer-checked/email:text-red-500",
+                vec!["peer-checked/email:text-red-500"],
+            ),
+            (
+                "group-[.is-open]/nav:flex",
+                vec!["group-[.is-open]/nav:flex"],
+            ),
+            (
+                r#"<div class="group/sidebar group-hover/sidebar:bg-white"></div>"#,
+                vec!["class", "group/sidebar", "group-hover/sidebar:bg-white"],
+            ),
+        ] {
+            assert_extract_sorted_candidates(input, expected);
+        }
+    }
+
     // https://github.com/tailwindlabs/tailwindcss/issues/17023
     #[test]
     fn test_js_embedded_in_php_syntax() {
@@ -996,6 +1077,168 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_jsx_spread_props_syntax() {
+        for input in [
+            r#"<Box {...props} className={cn(props.className, 'p-4')} />"#,
+            r#"<Box {...props}className={cn(props.className,'p-4')}/>"#,
+            r#"<Box className={cn(props?.className, `p-4`)} {...props} />"#,
+            r#"<Box {...props} className={cn(props['className'], "p-4")} />"#,
+            r#"<Box {...props} className={cn(...props.classNames, "p-4")} />"#,
+            r#"<Box {...{ ...props, className: cn(props.className, 'p-4') }} />"#,
+        ] {
+            assert_extract_candidates_contains(input, vec!["p-4"]);
+        }
+    }
+
+    #[test]
+    fn test_dom_class_api_syntax() {
+        for (input, expected) in [
+            // Multiple arguments
+            (
+                r#"el.classList.add('p-4', 'mt-2', "hover:bg-red-500");"#,
+                vec!["p-4", "mt-2", "hover:bg-red-500"],
+            ),
+            (
+                r#"el.classList.toggle('hidden',open);el.classList.remove("opacity-0","scale-95")"#,
+                vec!["hidden", "opacity-0", "scale-95"],
+            ),
+            // Setting the class attribute
+            (
+                r#"el.setAttribute('class', 'flex gap-2');el.setAttribute("class","grid")"#,
+                vec!["flex", "gap-2", "grid"],
+            ),
+        ] {
+            assert_extract_candidates_contains(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_lit_syntax() {
+        for (input, expected) in [
+            // `classMap` directive
+            (
+                r#"classMap({ 'flex': ok, "p-4": big, underline: isLink })"#,
+                vec!["flex", "p-4", "underline"],
+            ),
+            // Class attribute inside of an `html` template literal
+            (
+                r#"html`<span class="text-sm font-bold">${this.name}</span>`"#,
+                vec!["text-sm", "font-bold"],
+            ),
+            (
+                r#"html`<div class=${classMap({ 'items-center': centered })}></div>`"#,
+                vec!["items-center"],
+            ),
+        ] {
+            assert_extract_candidates_contains(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_raw_html_directives() {
+        for (input, expected) in [
+            // Astro
+            (
+                r#"<div set:html={'<p class="mt-4 text-lg">Hello</p>'} />"#,
+                vec!["mt-4", "text-lg"],
+            ),
+            (
+                r#"<div set:html={`<span class="font-bold underline"></span>`} />"#,
+                vec!["font-bold", "underline"],
+            ),
+            // Vue
+            (
+                r#"<div v-html="`<p class='px-2 py-1'></p>`"></div>"#,
+                vec!["px-2", "py-1"],
+            ),
+            // Svelte
+            (
+                r#"{@html '<em class="italic text-red-500"></em>'}"#,
+                vec!["italic", "text-red-500"],
+            ),
+        ] {
+            assert_extract_candidates_contains(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_tw_merge_syntax() {
+        for (input, expected) in [
+            // Conditional arguments
+            (
+                r#"twMerge('p-4', isActive && 'p-6', !isActive ? "mt-2" : `mt-4`)"#,
+                vec!["p-4", "p-6", "mt-2", "mt-4"],
+            ),
+            // Array arguments
+            (
+                r#"twMerge(['flex', cond && 'items-center'], [[`gap-2`]])"#,
+                vec!["flex", "items-center", "gap-2"],
+            ),
+            // Object arguments
+            (
+                r#"twMerge({ 'underline': on, "font-bold": bold, italic })"#,
+                vec!["underline", "font-bold", "italic"],
+            ),
+            // Nested helpers
+            (
+                r#"twMerge(clsx('px-2', { 'py-1': y }), cn(["text-sm"]))"#,
+                vec!["px-2", "py-1", "text-sm"],
+            ),
+            (
+                r#"<div className={twMerge(clsx("bg-red-500", props.className))} />"#,
+                vec!["bg-red-500"],
+            ),
+        ] {
+            assert_extract_candidates_contains(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_tailwind_variants_syntax() {
+        let input = r#"
+            const card = tv({
+              slots: {
+                base: 'md:flex bg-slate-100 rounded-xl p-8',
+                avatar: "w-24 h-24 md:w-48 md:h-auto",
+                wrapper: ['flex-1', 'pt-6 md:p-8'],
+              },
+              variants: {
+                color: {
+                  primary: { base: 'bg-blue-500', avatar: 'ring-blue-300' },
+                },
+                size: { sm: `text-sm`, lg: 'text-lg' },
+              },
+              compoundSlots: [{ slots: ['avatar', 'wrapper'], size: 'sm', class: 'gap-2' }],
+            });
+        "#;
+
+        assert_extract_candidates_contains(
+            input,
+            vec![
+                // Slots
+                "md:flex",
+                "bg-slate-100",
+                "rounded-xl",
+                "p-8",
+                "w-24",
+                "h-24",
+                "md:w-48",
+                "md:h-auto",
+                "flex-1",
+                "pt-6",
+                "md:p-8",
+                // Variants
+                "bg-blue-500",
+                "ring-blue-300",
+                "text-sm",
+                "text-lg",
+                // Compound slots
+                "gap-2",
+            ],
+        );
+    }
+
     // https://github.com/tailwindlabs/tailwindcss/issues/16978
     #[test]
     fn test_classes_containing_number_followed_by_dash_or_underscore() {
@@ -1018,6 +1261,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_arbitrary_properties_and_css_variables() {
+        let input =
+            r#"<div class="[--gap:1rem] [mask-type:luminance]" style="gap: var(--gap)"></div>"#;
+
+        // Arbitrary properties are candidates, even when they define a CSS variable
+        let candidates = extract_sorted_candidates(input);
+        assert!(candidates.contains(&"[--gap:1rem]"));
+        assert!(candidates.contains(&"[mask-type:luminance]"));
+        assert!(!candidates.contains(&"--gap"));
+
+        // References to the CSS variable are variables. The definition inside the arbitrary
+        // property is a variable as well, but the arbitrary property itself is not.
+        let variables = extract_sorted_css_variables(input);
+        assert_eq!(variables, vec!["--gap", "--gap"]);
+    }
+
     #[test]
     fn test_extract_css_variables() {
         for (input, expected) in [
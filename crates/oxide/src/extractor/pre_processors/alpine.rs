@@ -0,0 +1,68 @@
+use crate::extractor::pre_processors::html::Html;
+use crate::extractor::pre_processors::pre_processor::PreProcessor;
+use crate::extractor::pre_processors::script::Script;
+use bstr::ByteSlice;
+use regex::bytes::Regex;
+use std::sync;
+
+/// The state of Alpine components, e.g.: `x-data="{ open: false }"`
+static X_DATA_REGEX: sync::LazyLock<Regex> =
+    sync::LazyLock::new(|| Regex::new(r#"\bx-data\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap());
+
+/// Same as [Html], but the string values of Alpine's `x-data` objects are scanned as well, e.g.:
+/// `flex` and `gap-2` in `x-data="{ cls: 'flex gap-2' }"`.
+#[derive(Debug, Default)]
+pub struct Alpine;
+
+impl PreProcessor for Alpine {
+    fn process(&self, content: &[u8]) -> Vec<u8> {
+        let mut result = Html.process(content);
+
+        if !content.contains_str("x-data") {
+            return result;
+        }
+
+        // Only keep the string literals and object keys, other values are just state
+        for body in X_DATA_REGEX
+            .captures_iter(content)
+            .filter_map(|c| c.get(1).or_else(|| c.get(2)))
+        {
+            result[body.range()].copy_from_slice(&Script.process(body.as_bytes()));
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Alpine;
+    use crate::extractor::pre_processors::pre_processor::PreProcessor;
+    use crate::extractor::pre_processors::Html;
+
+    #[test]
+    fn test_alpine_x_data() {
+        for (input, expected) in [
+            (
+                r#"<div x-data="{ cls: 'flex gap-2', open: false }"></div>"#,
+                r#"<div x-data="  cls  'flex gap-2'  open         "></div>"#,
+            ),
+            (
+                r#"<div x-data='{ cls: "p-4 mt-2" }'></div>"#,
+                r#"<div x-data='  cls  "p-4 mt-2"  '></div>"#,
+            ),
+        ] {
+            Alpine::test(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_alpine_x_data_is_opt_in() {
+        let input = r#"<div x-data="{ cls: 'flex gap-2' }" :class="cls" class="p-4"></div>"#;
+
+        Alpine::test_extract_contains(input, vec!["flex", "gap-2", "p-4"]);
+
+        // Without the `Alpine` pre-processor, `x-data` is scanned like any other attribute
+        Html::test(input, input);
+    }
+}
@@ -0,0 +1,81 @@
+use crate::extractor::pre_processors::pre_processor::PreProcessor;
+use crate::extractor::pre_processors::script::Script;
+use bstr::ByteSlice;
+use regex::bytes::Regex;
+use std::sync;
+
+/// The frontmatter of a component, including the `---` fences. It has to be at the start of the
+/// file.
+static FRONTMATTER_REGEX: sync::LazyLock<Regex> =
+    sync::LazyLock::new(|| Regex::new(r"\A\s*(---[\s\S]*?\n---)").unwrap());
+
+#[derive(Debug, Default)]
+pub struct Astro;
+
+impl PreProcessor for Astro {
+    fn process(&self, content: &[u8]) -> Vec<u8> {
+        let mut result = content.to_vec();
+
+        // Only keep the string literals of the frontmatter. Imports, identifiers, etc. are not
+        // classes, but `const classes = 'p-4'` could be.
+        if let Some(frontmatter) = FRONTMATTER_REGEX.captures(content).and_then(|c| c.get(1)) {
+            result[frontmatter.range()].copy_from_slice(&Script.process(frontmatter.as_bytes()));
+        }
+
+        // The `class:list` directive itself is not a class, the strings in the array or object
+        // that is passed to it are.
+        result.replace("class:list=", "class     =")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Astro;
+    use crate::extractor::pre_processors::pre_processor::PreProcessor;
+
+    #[test]
+    fn test_astro_pre_processor() {
+        for (input, expected) in [
+            // Only keep strings in the frontmatter
+            (
+                "---\nimport Card from './Card.astro';\nconst a = 'p-4';\n---\n<div></div>",
+                "   \n                 './Card.astro' \n          'p-4' \n   \n<div></div>",
+            ),
+            // The `class:list` directive
+            (
+                "<div class:list={['flex', { 'font-bold': cond }]}></div>",
+                "<div class     ={['flex', { 'font-bold': cond }]}></div>",
+            ),
+            // Fences that are not at the start of the file are not frontmatter
+            ("<hr>\n---\nflex\n---", "<hr>\n---\nflex\n---"),
+        ] {
+            Astro::test(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_astro_component() {
+        let input = r#"---
+import Layout from '../layouts/Layout.astro';
+const { title, isActive } = Astro.props;
+const classes = "mt-2 text-lg";
+---
+<Layout title={title}>
+  <h1 class={classes}>{title}</h1>
+  <div class:list={['flex', { 'font-bold': isActive }, isActive && 'gap-2']}></div>
+  <p class:list={{ 'underline': isActive }}></p>
+</Layout>
+"#;
+
+        Astro::test_extract_contains(
+            input,
+            vec!["mt-2", "text-lg", "flex", "font-bold", "gap-2", "underline"],
+        );
+
+        let output = Astro.process(input.as_bytes());
+        let output = String::from_utf8_lossy(&output);
+        assert!(!output.contains("import"));
+        assert!(!output.contains("Astro.props"));
+        assert!(!output.contains("class:list"));
+    }
+}
@@ -0,0 +1,140 @@
+use crate::extractor::pre_processors::php::Php;
+use crate::extractor::pre_processors::pre_processor::PreProcessor;
+use crate::extractor::pre_processors::script::Script;
+use bstr::ByteSlice;
+use regex::bytes::Regex;
+use std::sync;
+
+/// PHP code inside of echoes and `@php` blocks, e.g.: `{{ $title }}` or `{!! $html !!}`
+static CODE_REGEX: sync::LazyLock<Regex> = sync::LazyLock::new(|| {
+    Regex::new(r"\{\{([\s\S]*?)\}\}|\{!!([\s\S]*?)!!\}|@php\b([\s\S]*?)@endphp").unwrap()
+});
+
+static COMMENT_REGEX: sync::LazyLock<Regex> =
+    sync::LazyLock::new(|| Regex::new(r"\{\{--[\s\S]*?--\}\}").unwrap());
+
+#[derive(Debug, Default)]
+pub struct Blade;
+
+impl PreProcessor for Blade {
+    fn process(&self, content: &[u8]) -> Vec<u8> {
+        // Blade templates can contain plain PHP tags as well
+        let mut result = Php.process(content);
+
+        // Only keep the string literals of PHP code. Variables like `$title` are not classes, but
+        // `{{ $active ? 'font-bold' : '' }}` could be.
+        for body in CODE_REGEX
+            .captures_iter(content)
+            .filter_map(|c| c.get(1).or_else(|| c.get(2)).or_else(|| c.get(3)))
+        {
+            result[body.range()].copy_from_slice(&Script.process(body.as_bytes()));
+        }
+
+        // Same for the arguments of `@class` directives, e.g.: `@class(['p-4' => $padded])`
+        let mut idx = 0;
+        while let Some(offset) = content[idx..].find(b"@class(") {
+            let start = idx + offset + b"@class(".len();
+            let end = closing_paren(content, start);
+
+            result[start..end].copy_from_slice(&Script.process(&content[start..end]));
+            idx = end;
+        }
+
+        // Comments are dropped entirely
+        for comment in COMMENT_REGEX.find_iter(content) {
+            result[comment.range()].fill(b' ');
+        }
+
+        result
+    }
+}
+
+// The position of the `)` that closes the arguments starting at `start`, skipping parentheses
+// inside of strings. The end of the content is used for unbalanced arguments.
+fn closing_paren(content: &[u8], start: usize) -> usize {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut idx = start;
+
+    while idx < content.len() {
+        match (quote, content[idx]) {
+            (Some(_), b'\\') => idx += 1,
+            (Some(q), x) if x == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'\'' | b'"') => quote = Some(content[idx]),
+            (None, b'(') => depth += 1,
+            (None, b')') if depth == 0 => return idx,
+            (None, b')') => depth -= 1,
+            (None, _) => {}
+        }
+
+        idx += 1;
+    }
+
+    content.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Blade;
+    use crate::extractor::pre_processors::pre_processor::PreProcessor;
+
+    #[test]
+    fn test_blade_pre_processor() {
+        for (input, expected) in [
+            // Echoes only keep their strings
+            (
+                r#"<h1 class="flex">{{ $title }}</h1>"#,
+                r#"<h1 class="flex">{{        }}</h1>"#,
+            ),
+            (
+                r#"<div class="{{ $active ? 'font-bold' : '' }}"></div>"#,
+                r#"<div class="{{           'font-bold'   '' }}"></div>"#,
+            ),
+            (r#"{!! $html !!}"#, r#"{!!       !!}"#),
+            // The arguments of `@class` directives only keep their strings
+            (
+                r#"@class(['p-4' => $padded, 'mt-2'])"#,
+                r#"@class( 'p-4'             'mt-2' )"#,
+            ),
+            // Comments are dropped
+            (r#"{{-- 'underline' --}}"#, r#"                     "#),
+        ] {
+            Blade::test(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_blade_template() {
+        let input = r#"
+            {{-- Card component --}}
+            <div @class(['p-4', 'font-bold' => $isActive, 'text-gray-500' => ! $isActive])>
+              <h2 class="text-lg">{{ $foo }}</h2>
+              <span class="{{ $highlight ? 'bg-yellow-200' : 'bg-white' }}"></span>
+              @php
+                $classes = 'rounded-lg shadow';
+              @endphp
+            </div>
+        "#;
+
+        Blade::test_extract_contains(
+            input,
+            vec![
+                "p-4",
+                "font-bold",
+                "text-gray-500",
+                "text-lg",
+                "bg-yellow-200",
+                "bg-white",
+                "rounded-lg",
+                "shadow",
+            ],
+        );
+
+        let output = Blade.process(input.as_bytes());
+        let output = String::from_utf8_lossy(&output);
+        assert!(!output.contains("$foo"));
+        assert!(!output.contains("isActive"));
+        assert!(!output.contains("Card"));
+    }
+}
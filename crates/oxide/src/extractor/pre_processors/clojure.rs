@@ -46,6 +46,17 @@ impl PreProcessor for Clojure {
                     result[cursor.pos] = b' ';
                 }
 
+                // Ids of hiccup elements are not classes, e.g.: `#id` in `[:div.foo#id]`
+                b'#' if cursor.prev.is_ascii_alphanumeric() || cursor.prev == b'-' => {
+                    result[cursor.pos] = b' ';
+
+                    while cursor.next.is_ascii_alphanumeric() || matches!(cursor.next, b'-' | b'_')
+                    {
+                        cursor.advance();
+                        result[cursor.pos] = b' ';
+                    }
+                }
+
                 // Consume everything else
                 _ => {}
             };
@@ -136,6 +147,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_hiccup_ids() {
+        for (input, expected) in [
+            ("[:div.foo.bar#id]", "[ div foo bar   ]"),
+            ("[:div#main.flex.gap-2]", "[ div      flex gap-2]"),
+            // Reader macros are not ids
+            ("#{:a :b} #(inc %)", "#{ a  b} #(inc %)"),
+        ] {
+            Clojure::test(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_hiccup_keyword_classes() {
+        let input = r#"
+            [:div.foo.bar#id
+             [:section#main.flex.gap-2 {:class "p-4 mt-2"}]
+             [:span {:class (class "underline" (when active "font-bold"))}]]
+        "#;
+
+        Clojure::test_extract_contains(
+            input,
+            vec![
+                "foo",
+                "bar",
+                "flex",
+                "gap-2",
+                "p-4",
+                "mt-2",
+                "underline",
+                "font-bold",
+            ],
+        );
+
+        // The shorthand classes are never merged into a single token
+        let output = Clojure.process(input.as_bytes());
+        let output = String::from_utf8_lossy(&output);
+        assert!(!output.contains("foo.bar"));
+        assert!(!output.contains("bar#id"));
+        assert!(!output.contains("main"));
+    }
+
     #[test]
     fn test_special_characters_are_valid_in_strings() {
         // In this case the `:` and `.` should not be replaced by ` ` because they are inside a
@@ -0,0 +1,88 @@
+use crate::extractor::pre_processors::json::JsonValues;
+use crate::extractor::pre_processors::pre_processor::PreProcessor;
+use bstr::ByteSlice;
+use regex::bytes::Regex;
+use std::sync;
+
+static JSON_SCRIPT_REGEX: sync::LazyLock<Regex> = sync::LazyLock::new(|| {
+    Regex::new(r#"<script\b[^>]*\btype=["']application/json["'][^>]*>([\s\S]*?)</script>"#).unwrap()
+});
+
+#[derive(Debug, Default)]
+pub struct Html;
+
+impl PreProcessor for Html {
+    fn process(&self, content: &[u8]) -> Vec<u8> {
+        let mut result = content.to_vec();
+
+        // Fast path, most HTML files don't embed any JSON
+        if !content.contains_str("application/json") {
+            return result;
+        }
+
+        // Only the values of embedded JSON can contain classes, the keys are just config.
+        for body in JSON_SCRIPT_REGEX
+            .captures_iter(content)
+            .filter_map(|c| c.get(1))
+        {
+            result[body.range()].copy_from_slice(&JsonValues.process(body.as_bytes()));
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Html;
+    use crate::extractor::pre_processors::pre_processor::PreProcessor;
+
+    #[test]
+    fn test_html_json_script() {
+        for (input, expected) in [
+            // Only keep the values of JSON script blocks
+            (
+                r#"<script type="application/json">{"a": "flex", "b": true}</script>"#,
+                r#"<script type="application/json">      "flex"            </script>"#,
+            ),
+            // Other scripts are untouched
+            (
+                r#"<script type="module">{"a": "flex"}</script>"#,
+                r#"<script type="module">{"a": "flex"}</script>"#,
+            ),
+        ] {
+            Html::test(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_html_json_script_extraction() {
+        let input = r#"
+            <script type="application/json" id="theme">
+              { "button": "bg-red-500 px-4", "dark-mode": true, "sizes": ["text-sm"] }
+            </script>
+            <div class="flex"></div>
+        "#;
+
+        Html::test_extract_contains(input, vec!["bg-red-500", "px-4", "text-sm", "flex"]);
+
+        let output = Html.process(input.as_bytes());
+        let output = String::from_utf8_lossy(&output);
+        assert!(!output.contains("button"));
+        assert!(!output.contains("dark-mode"));
+    }
+
+    #[test]
+    fn test_template_extraction() {
+        let input = r#"
+            <template id="card">
+              <div class="p-4 rounded-lg"><slot name="title" class="font-bold"></slot></div>
+            </template>
+            <my-card>
+              <template shadowrootmode="open"><p class="text-sm">Hello</p></template>
+            </my-card>
+        "#;
+
+        Html::test_extract_contains(input, vec!["p-4", "rounded-lg", "font-bold", "text-sm"]);
+    }
+}
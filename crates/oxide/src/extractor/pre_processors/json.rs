@@ -46,10 +46,284 @@ impl PreProcessor for Json {
     }
 }
 
+/// Only keeps the string values of a JSON document, object keys and all other tokens are
+/// replaced with spaces.
+#[derive(Debug, Default)]
+pub struct JsonValues;
+
+impl PreProcessor for JsonValues {
+    fn process(&self, content: &[u8]) -> Vec<u8> {
+        let len = content.len();
+        let mut result = content.to_vec();
+        let mut cursor = cursor::Cursor::new(content);
+
+        while cursor.pos < len {
+            match cursor.curr {
+                b'"' => {
+                    let start = cursor.pos;
+                    cursor.advance();
+
+                    while cursor.pos < len {
+                        match cursor.curr {
+                            // Escaped character, skip ahead to the next character
+                            b'\\' => cursor.advance_twice(),
+
+                            // End of the string
+                            b'"' => break,
+
+                            // Everything else is valid
+                            _ => cursor.advance(),
+                        };
+                    }
+
+                    let end = cursor.pos.min(len - 1);
+
+                    // A string followed by a `:` is an object key
+                    let is_key = content[end + 1..]
+                        .iter()
+                        .find(|x| !x.is_ascii_whitespace())
+                        .is_some_and(|x| *x == b':');
+
+                    if is_key {
+                        result[start..=end].fill(b' ');
+                    }
+                }
+
+                // Keep whitespace to preserve line boundaries
+                x if x.is_ascii_whitespace() => {}
+
+                // Replace everything else with spaces
+                _ => {
+                    result[cursor.pos] = b' ';
+                }
+            };
+
+            cursor.advance();
+        }
+
+        result
+    }
+}
+
+/// Only keeps the string values under the given key paths of a JSON document, everything else is
+/// replaced with spaces. Key paths are dotted, and `*` matches any key or array index, e.g.:
+/// `components.*.className`. All strings nested inside of a matching value are kept as well.
+#[derive(Debug, Clone, Default)]
+pub struct JsonKeyPaths {
+    paths: Vec<Vec<String>>,
+}
+
+impl JsonKeyPaths {
+    pub fn new<S: AsRef<str>>(paths: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            paths: paths
+                .into_iter()
+                .map(|path| path.as_ref().split('.').map(|x| x.to_owned()).collect())
+                .collect(),
+        }
+    }
+
+    fn is_match(&self, path: &[Vec<u8>]) -> bool {
+        self.paths.iter().any(|expected| {
+            expected.len() <= path.len()
+                && expected
+                    .iter()
+                    .zip(path)
+                    .all(|(expected, key)| expected == "*" || expected.as_bytes() == key)
+        })
+    }
+}
+
+impl PreProcessor for JsonKeyPaths {
+    fn process(&self, content: &[u8]) -> Vec<u8> {
+        // Start with nothing but whitespace, and copy the matching strings back in
+        let mut result: Vec<u8> = content
+            .iter()
+            .map(|x| if x.is_ascii_whitespace() { *x } else { b' ' })
+            .collect();
+
+        let mut walker = JsonWalker {
+            content,
+            pos: 0,
+            key_paths: self,
+            result: &mut result,
+        };
+
+        while walker.pos < content.len() {
+            walker.value(&mut vec![], 0);
+        }
+
+        result
+    }
+}
+
+/// Objects and arrays that are nested deeper than this are blanked as a whole, instead of walking
+/// them recursively. This prevents a stack overflow on deeply nested content.
+const MAX_DEPTH: usize = 128;
+
+struct JsonWalker<'a> {
+    content: &'a [u8],
+    pos: usize,
+    key_paths: &'a JsonKeyPaths,
+    result: &'a mut Vec<u8>,
+}
+
+impl JsonWalker<'_> {
+    fn value(&mut self, path: &mut Vec<Vec<u8>>, depth: usize) {
+        self.skip_whitespace();
+
+        match self.content.get(self.pos) {
+            Some(b'{' | b'[') if depth >= MAX_DEPTH => self.skip_nested(),
+
+            Some(b'{') => {
+                self.pos += 1;
+
+                loop {
+                    self.skip_whitespace();
+
+                    match self.content.get(self.pos) {
+                        Some(b'"') => {
+                            let (start, end) = self.string();
+                            path.push(self.content[start + 1..end].to_vec());
+
+                            self.skip_whitespace();
+                            if self.content.get(self.pos) == Some(&b':') {
+                                self.pos += 1;
+                                self.value(path, depth + 1);
+                            }
+
+                            path.pop();
+                        }
+                        Some(b'}') => {
+                            self.pos += 1;
+                            break;
+                        }
+                        Some(b',') => self.pos += 1,
+                        Some(_) => self.value(path, depth + 1),
+                        None => break,
+                    }
+                }
+            }
+
+            Some(b'[') => {
+                self.pos += 1;
+                let mut index = 0;
+
+                loop {
+                    self.skip_whitespace();
+
+                    match self.content.get(self.pos) {
+                        Some(b']') => {
+                            self.pos += 1;
+                            break;
+                        }
+                        Some(b',') => {
+                            self.pos += 1;
+                            index += 1;
+                        }
+                        Some(b'}') => self.pos += 1,
+                        Some(_) => {
+                            path.push(index.to_string().into_bytes());
+                            self.value(path, depth + 1);
+                            path.pop();
+                        }
+                        None => break,
+                    }
+                }
+            }
+
+            Some(b'"') => {
+                let (start, end) = self.string();
+
+                if self.key_paths.is_match(path) {
+                    let end = end.min(self.content.len() - 1);
+                    self.result[start..=end].copy_from_slice(&self.content[start..=end]);
+                }
+            }
+
+            // Numbers, booleans, `null` and invalid tokens
+            Some(_) => {
+                self.pos += 1;
+
+                while self.pos < self.content.len()
+                    && !matches!(self.content[self.pos], b',' | b']' | b'}' | b'"')
+                    && !self.content[self.pos].is_ascii_whitespace()
+                {
+                    self.pos += 1;
+                }
+            }
+
+            None => {}
+        }
+    }
+
+    // Consume a string, and return the position of the opening and closing quotes
+    fn string(&mut self) -> (usize, usize) {
+        let start = self.pos;
+        self.pos += 1;
+
+        while self.pos < self.content.len() {
+            match self.content[self.pos] {
+                // Escaped character, skip ahead to the next character
+                b'\\' => self.pos += 2,
+
+                // End of the string
+                b'"' => break,
+
+                // Everything else is valid
+                _ => self.pos += 1,
+            }
+        }
+
+        // Unterminated strings end at the end of the content, e.g.: `{"a\`
+        let end = self.pos.min(self.content.len());
+        self.pos = end + 1;
+
+        (start, end)
+    }
+
+    // Consume an object or array, including everything nested inside of it
+    fn skip_nested(&mut self) {
+        let mut depth = 0;
+
+        while let Some(x) = self.content.get(self.pos) {
+            match x {
+                b'"' => {
+                    self.string();
+                    continue;
+                }
+                b'{' | b'[' => depth += 1,
+                b'}' | b']' => {
+                    depth -= 1;
+
+                    if depth == 0 {
+                        self.pos += 1;
+                        return;
+                    }
+                }
+                _ => {}
+            }
+
+            self.pos += 1;
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .content
+            .get(self.pos)
+            .is_some_and(|x| x.is_ascii_whitespace())
+        {
+            self.pos += 1;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Json;
+    use super::{Json, JsonKeyPaths, JsonValues};
     use crate::extractor::pre_processors::pre_processor::PreProcessor;
+    use crate::extractor::{Extracted, Extractor};
 
     #[test]
     fn test_json_pre_processor() {
@@ -60,4 +334,95 @@ mod tests {
 
         Json::test(input, expected);
     }
+
+    #[test]
+    fn test_json_values_pre_processor() {
+        let (input, expected) = (
+            r#"{"flex": true, "theme": {"button" : ["px-4 \"x\"", 2]}}"#,
+            r#"                                     "px-4 \"x\""      "#,
+        );
+
+        JsonValues::test(input, expected);
+    }
+
+    #[test]
+    fn test_json_key_paths_pre_processor() {
+        let (input, expected) = (
+            r#"{"a": {"className": "flex", "label": "Save"}, "b": ["p-4", "x"]}"#,
+            r#"                    "flex"                                      "#,
+        );
+
+        let actual = JsonKeyPaths::new(["a.className"]).process(input.as_bytes());
+        assert_eq!(String::from_utf8_lossy(&actual), expected);
+    }
+
+    #[test]
+    fn test_json_key_paths_extraction() {
+        let input = r#"
+            {
+              "title": "Design tokens",
+              "components": {
+                "button": { "className": "px-4 py-2 font-bold", "label": "underline" },
+                "card": {
+                  "className": ["rounded-lg", "shadow-md"],
+                  "description": "italic text"
+                }
+              },
+              "safelist": [{ "className": "bg-red-500" }, { "label": "bg-blue-500" }]
+            }
+        "#;
+
+        let key_paths = JsonKeyPaths::new(["components.*.className", "safelist.*.className"]);
+
+        let output = key_paths.process(input.as_bytes());
+        let mut candidates: Vec<_> = Extractor::new(&output)
+            .extract()
+            .into_iter()
+            .filter_map(|x| match x {
+                Extracted::Candidate(bytes) => std::str::from_utf8(bytes).ok(),
+                Extracted::CssVariable(_) => None,
+            })
+            .collect();
+        candidates.sort();
+
+        assert_eq!(
+            candidates,
+            vec![
+                "bg-red-500",
+                "font-bold",
+                "px-4",
+                "py-2",
+                "rounded-lg",
+                "shadow-md",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_key_paths_unterminated_string() {
+        for input in [r#"{"a\"#, r#"{"a": "b\"#, r#"["\"#, r#"""#] {
+            let actual = JsonKeyPaths::new(["a"]).process(input.as_bytes());
+            assert_eq!(actual.len(), input.len());
+        }
+    }
+
+    #[test]
+    fn test_json_key_paths_deeply_nested() {
+        let depth = 100_000;
+        let input = format!(
+            r#"{{"a": "flex", "b": {}"underline"{}, "c": "px-4"}}"#,
+            "[".repeat(depth),
+            "]".repeat(depth)
+        );
+
+        let actual = JsonKeyPaths::new(["*"]).process(input.as_bytes());
+        assert_eq!(actual.len(), input.len());
+
+        // Everything that is nested too deeply is blanked, the rest is still walked
+        let actual = String::from_utf8(actual).unwrap();
+        assert_eq!(
+            actual.split_whitespace().collect::<Vec<_>>(),
+            vec![r#""flex""#, r#""px-4""#]
+        );
+    }
 }
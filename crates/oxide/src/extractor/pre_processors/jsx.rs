@@ -0,0 +1,238 @@
+use crate::extractor::pre_processors::pre_processor::PreProcessor;
+use bstr::ByteSlice;
+
+const CLASS_NAME_EXPRESSION: &[u8] = b"className={";
+const APPLY: &[u8] = b"@apply ";
+
+/// Removes member access expressions from `className={…}` expressions, e.g.: `styles.foo`. Their
+/// segments look like candidates, but they never are. String literals and other code are kept.
+///
+/// The `;` after an `@apply` in CSS-in-JS templates is removed as well, e.g.: `` css`@apply p-4;` ``
+#[derive(Debug, Default)]
+pub struct Jsx;
+
+impl PreProcessor for Jsx {
+    fn process(&self, content: &[u8]) -> Vec<u8> {
+        let mut result = content.to_vec();
+        let len = content.len();
+
+        // The last utility of an `@apply` is followed by a `;`, e.g.: `@apply flex p-4;`
+        for start in content.find_iter(APPLY) {
+            let end = content[start..]
+                .iter()
+                .position(|x| matches!(x, b';' | b'\n' | b'`' | b'}'))
+                .map(|end| start + end);
+
+            if let Some(end) = end.filter(|end| content[*end] == b';') {
+                result[end] = b' ';
+            }
+        }
+
+        // Fast path, most files don't use expressions for their classes
+        if !content.contains_str(CLASS_NAME_EXPRESSION) {
+            return result;
+        }
+
+        for start in content.find_iter(CLASS_NAME_EXPRESSION) {
+            let mut pos = start + CLASS_NAME_EXPRESSION.len();
+            let mut depth = 1;
+
+            while pos < len && depth > 0 {
+                match content[pos] {
+                    // Skip over strings, they can contain anything
+                    quote @ (b'"' | b'\'' | b'`') => {
+                        pos += 1;
+
+                        while pos < len && content[pos] != quote {
+                            // Escaped character, skip ahead to the next character
+                            if content[pos] == b'\\' {
+                                pos += 1;
+                            }
+
+                            pos += 1;
+                        }
+                    }
+
+                    b'{' => depth += 1,
+                    b'}' => depth -= 1,
+
+                    // Identifiers, e.g.: `styles` in `styles.foo`
+                    x if is_identifier_start(x) && !is_identifier(content[pos - 1]) => {
+                        let end = member_expression_end(content, pos);
+
+                        if let Some(end) = end {
+                            result[pos..end].fill(b' ');
+                            pos = end;
+                        } else {
+                            while pos < len && is_identifier(content[pos]) {
+                                pos += 1;
+                            }
+                        }
+
+                        continue;
+                    }
+
+                    _ => {}
+                }
+
+                pos += 1;
+            }
+        }
+
+        result
+    }
+}
+
+// The end of a member access expression starting at `start`, e.g.: `styles.foo` or
+// `props?.styles.foo`. Returns `None` for plain identifiers.
+fn member_expression_end(content: &[u8], start: usize) -> Option<usize> {
+    let len = content.len();
+    let mut pos = start;
+    let mut is_member_expression = false;
+
+    loop {
+        while pos < len && is_identifier(content[pos]) {
+            pos += 1;
+        }
+
+        let accessor = match content.get(pos..) {
+            Some([b'.', next, ..]) if is_identifier_start(*next) => 1,
+            Some([b'?', b'.', next, ..]) if is_identifier_start(*next) => 2,
+            _ => break,
+        };
+
+        pos += accessor;
+        is_member_expression = true;
+    }
+
+    is_member_expression.then_some(pos)
+}
+
+#[inline(always)]
+fn is_identifier_start(x: u8) -> bool {
+    x.is_ascii_alphabetic() || x == b'_' || x == b'$'
+}
+
+#[inline(always)]
+fn is_identifier(x: u8) -> bool {
+    x.is_ascii_alphanumeric() || x == b'_' || x == b'$'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Jsx;
+    use crate::extractor::pre_processors::pre_processor::PreProcessor;
+
+    #[test]
+    fn test_jsx_pre_processor() {
+        for (input, expected) in [
+            // Member access expressions are removed
+            (
+                "<div className={styles.foo ? 'p-4' : 'p-6'} />",
+                "<div className={           ? 'p-4' : 'p-6'} />",
+            ),
+            (
+                "<div className={props?.styles.foo} />",
+                "<div className={                 } />",
+            ),
+            // Plain identifiers and strings are kept
+            (
+                r#"<div className={cn({ underline: isLink }, "flex")} />"#,
+                r#"<div className={cn({ underline: isLink }, "flex")} />"#,
+            ),
+            // Strings containing dots are kept
+            (
+                "<div className={`${a.b} px-2.5 styles.foo`} />",
+                "<div className={`${a.b} px-2.5 styles.foo`} />",
+            ),
+            // Member access outside of `className` is kept
+            ("const x = styles.foo", "const x = styles.foo"),
+            // `@apply` in CSS-in-JS templates
+            ("css`@apply p-4 flex;`", "css`@apply p-4 flex `"),
+            (
+                "css`@apply p-4; color: red;`",
+                "css`@apply p-4  color: red;`",
+            ),
+        ] {
+            Jsx::test(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_jsx_class_name_expression_extraction() {
+        let input = r#"
+            <div className={styles.foo ? 'p-4' : 'p-6'} />
+            <div className={cn(styles.wrapper, isOpen && "flex")} />
+        "#;
+
+        Jsx::test_extract_contains(input, vec!["p-4", "p-6", "flex"]);
+
+        let output = Jsx.process(input.as_bytes());
+        let output = String::from_utf8_lossy(&output);
+        assert!(!output.contains("foo"));
+        assert!(!output.contains("wrapper"));
+    }
+
+    #[test]
+    fn test_class_name_constants_extraction() {
+        let input = r#"
+            const buttonClass = `px-4 py-2 ${active ? 'bg-blue-500' : ''} hover:underline`;
+            export const cardClass = "rounded-lg shadow-md";
+
+            export default () => <button className={buttonClass} />;
+        "#;
+
+        Jsx::test_extract_contains(
+            input,
+            vec![
+                "px-4",
+                "py-2",
+                "bg-blue-500",
+                "hover:underline",
+                "rounded-lg",
+                "shadow-md",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_css_in_js_extraction() {
+        let input = r#"
+            const button = css`
+              @apply p-4 hover:bg-red-500;
+              color: red;
+            `;
+            const base = css`@apply flex-col;`;
+            const c = cx('flex', styles, { 'items-center': centered }, isOpen && "gap-2");
+        "#;
+
+        Jsx::test_extract_contains(
+            input,
+            vec![
+                "p-4",
+                "hover:bg-red-500",
+                "flex-col",
+                "flex",
+                "items-center",
+                "gap-2",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_create_element_extraction() {
+        // Props objects are extracted regardless of the pragma
+        let input = r#"
+            React.createElement('div', { className: 'p-4 flex' },
+              h('span', { class: "font-bold" }),
+              jsx("a", { className: `underline`, children: "Home" }),
+              customPragma('p', { className: 'text-sm italic' }),
+            );
+        "#;
+
+        Jsx::test_extract_contains(
+            input,
+            vec!["p-4", "flex", "font-bold", "underline", "text-sm", "italic"],
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use crate::extractor::pre_processors::json::JsonValues;
+use crate::extractor::pre_processors::pre_processor::PreProcessor;
+use bstr::ByteSlice;
+use regex::bytes::Regex;
+use std::sync;
+
+static SCHEMA_REGEX: sync::LazyLock<Regex> = sync::LazyLock::new(|| {
+    Regex::new(r"\{%-?\s*schema\s*-?%\}([\s\S]*?)\{%-?\s*endschema\s*-?%\}").unwrap()
+});
+
+#[derive(Debug, Default)]
+pub struct Liquid;
+
+impl PreProcessor for Liquid {
+    fn process(&self, content: &[u8]) -> Vec<u8> {
+        let mut result = content.to_vec();
+
+        // Fast path, only Shopify sections embed a schema
+        if !content.contains_str("endschema") {
+            return result;
+        }
+
+        // Only the values of the schema can contain classes, e.g.: the `default` of a setting.
+        // The keys are just config.
+        for body in SCHEMA_REGEX.captures_iter(content).filter_map(|c| c.get(1)) {
+            result[body.range()].copy_from_slice(&JsonValues.process(body.as_bytes()));
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Liquid;
+    use crate::extractor::pre_processors::pre_processor::PreProcessor;
+
+    #[test]
+    fn test_liquid_schema() {
+        for (input, expected) in [
+            // Only keep the values of the schema block
+            (
+                r#"{% schema %}{"default": "p-4"}{% endschema %}"#,
+                r#"{% schema %}            "p-4" {% endschema %}"#,
+            ),
+            // Whitespace control
+            (
+                r#"{%- schema -%}{"a": "flex"}{%- endschema -%}"#,
+                r#"{%- schema -%}      "flex" {%- endschema -%}"#,
+            ),
+            // The rest of the template is untouched
+            (
+                r#"<div class="{{ section.settings.a }} flex"></div>"#,
+                r#"<div class="{{ section.settings.a }} flex"></div>"#,
+            ),
+        ] {
+            Liquid::test(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_liquid_schema_extraction() {
+        let input = r#"
+            <section class="{{ section.settings.padding }} mx-auto">
+              <h2 class="text-2xl">{{ section.settings.title }}</h2>
+            </section>
+
+            {% schema %}
+              {
+                "name": "Hero",
+                "settings": [
+                  { "type": "text", "id": "padding", "label": "Padding", "default": "p-4" }
+                ]
+              }
+            {% endschema %}
+        "#;
+
+        Liquid::test_extract_contains(input, vec!["mx-auto", "text-2xl", "p-4"]);
+
+        let output = Liquid.process(input.as_bytes());
+        let output = String::from_utf8_lossy(&output);
+        assert!(!output.contains("\"settings\""));
+        assert!(!output.contains("\"default\""));
+    }
+}
@@ -0,0 +1,180 @@
+use crate::extractor::pre_processors::pre_processor::PreProcessor;
+
+#[derive(Debug, Default)]
+pub struct Markdown;
+
+impl PreProcessor for Markdown {
+    fn process(&self, content: &[u8]) -> Vec<u8> {
+        let mut result = content.to_vec();
+
+        // The opening fence of the current fenced code block, e.g.: "```" or "~~~~"
+        let mut fence: Option<(u8, usize)> = None;
+
+        let mut start = 0;
+        while start < content.len() {
+            let end = content[start..]
+                .iter()
+                .position(|x| *x == b'\n')
+                .map_or(content.len(), |idx| start + idx);
+
+            let line = &content[start..end];
+
+            match (fence, code_fence(line)) {
+                // Opening fence, the info string is dropped as well, e.g.: "```html"
+                (None, Some(opening)) => {
+                    fence = Some(opening);
+                    blank(&mut result[start..end]);
+                }
+
+                // Closing fence, this has to be at least as long as the opening fence and can't
+                // have an info string
+                (Some((char, len)), Some((closing_char, closing_len)))
+                    if closing_char == char
+                        && closing_len >= len
+                        && line.trim_ascii()[closing_len..].is_empty() =>
+                {
+                    fence = None;
+                    blank(&mut result[start..end]);
+                }
+
+                // Everything inside of a fenced code block is code
+                (Some(_), _) => blank(&mut result[start..end]),
+
+                // Raw HTML and prose is kept, only inline code spans are dropped
+                (None, None) => blank_code_spans(line, &mut result[start..end]),
+            }
+
+            start = end + 1;
+        }
+
+        result
+    }
+}
+
+// The fence character and its length if the line opens or closes a fenced code block, e.g.:
+// "```js". Fences can be indented by up to 3 spaces.
+fn code_fence(line: &[u8]) -> Option<(u8, usize)> {
+    let indent = line.iter().take_while(|x| **x == b' ').count();
+    if indent > 3 {
+        return None;
+    }
+
+    let line = &line[indent..];
+    let char = *line.first()?;
+    if char != b'`' && char != b'~' {
+        return None;
+    }
+
+    let len = line.iter().take_while(|x| **x == char).count();
+    (len >= 3).then_some((char, len))
+}
+
+// Inline code spans start and end with a backtick string of the same length, e.g.: `code` or
+// ``code with a ` in it``.
+fn blank_code_spans(line: &[u8], result: &mut [u8]) {
+    let mut idx = 0;
+
+    while idx < line.len() {
+        if line[idx] != b'`' {
+            idx += 1;
+            continue;
+        }
+
+        let len = line[idx..].iter().take_while(|x| **x == b'`').count();
+
+        // Find the closing backtick string of the exact same length
+        let mut end = idx + len;
+        let closing = loop {
+            let Some(offset) = line[end..].iter().position(|x| *x == b'`') else {
+                break None;
+            };
+
+            let pos = end + offset;
+            let closing_len = line[pos..].iter().take_while(|x| **x == b'`').count();
+            if closing_len == len {
+                break Some(pos + closing_len);
+            }
+
+            end = pos + closing_len;
+        };
+
+        match closing {
+            Some(closing) => {
+                blank(&mut result[idx..closing]);
+                idx = closing;
+            }
+
+            // Unmatched backticks are literal text
+            None => idx += len,
+        }
+    }
+}
+
+fn blank(bytes: &mut [u8]) {
+    for byte in bytes.iter_mut().filter(|x| !x.is_ascii_whitespace()) {
+        *byte = b' ';
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Markdown;
+    use crate::extractor::pre_processors::pre_processor::PreProcessor;
+
+    #[test]
+    fn test_markdown_pre_processor() {
+        for (input, expected) in [
+            // Raw HTML is kept
+            (
+                r#"<div class="p-4 flex"></div>"#,
+                r#"<div class="p-4 flex"></div>"#,
+            ),
+            // Inline code spans are dropped
+            (
+                r#"Use `class="underline"` for links"#,
+                r#"Use                     for links"#,
+            ),
+            (
+                r#"A ``span with ` inside`` here"#,
+                r#"A                        here"#,
+            ),
+            // Unmatched backticks are kept
+            ("It`s flex", "It`s flex"),
+            // Fenced code blocks are dropped
+            (
+                "```html\n<div class=\"italic\"></div>\n```\n<p class=\"mt-2\"></p>",
+                "       \n                          \n   \n<p class=\"mt-2\"></p>",
+            ),
+            (
+                "~~~~\n```\nfont-bold\n~~~~\nunderline",
+                "    \n   \n         \n    \nunderline",
+            ),
+        ] {
+            Markdown::test(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_markdown_html_blocks_and_code() {
+        let input = r#"
+# Buttons
+
+<div class="rounded-lg bg-white p-4">
+  <button class="font-bold">Click me</button>
+</div>
+
+Add `class="text-red-500"` to make the button red.
+
+```html
+<button class="text-blue-500">Example</button>
+```
+        "#;
+
+        Markdown::test_extract_contains(input, vec!["rounded-lg", "bg-white", "p-4", "font-bold"]);
+
+        let output = Markdown.process(input.as_bytes());
+        let output = String::from_utf8_lossy(&output);
+        assert!(!output.contains("text-red-500"));
+        assert!(!output.contains("text-blue-500"));
+    }
+}
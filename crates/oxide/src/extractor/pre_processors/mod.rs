@@ -1,21 +1,47 @@
+pub mod alpine;
+pub mod astro;
+pub mod blade;
 pub mod clojure;
 pub mod haml;
+pub mod html;
 pub mod json;
+pub mod jsx;
+pub mod liquid;
+pub mod markdown;
+pub mod php;
 pub mod pre_processor;
+pub mod pre_processor_kind;
+pub mod properties;
 pub mod pug;
 pub mod razor;
 pub mod ruby;
+pub mod script;
+pub mod shell;
 pub mod slim;
+pub mod style;
 pub mod svelte;
 pub mod vue;
 
+pub use alpine::*;
+pub use astro::*;
+pub use blade::*;
 pub use clojure::*;
 pub use haml::*;
+pub use html::*;
 pub use json::*;
+pub use jsx::*;
+pub use liquid::*;
+pub use markdown::*;
+pub use php::*;
 pub use pre_processor::*;
+pub use pre_processor_kind::*;
+pub use properties::*;
 pub use pug::*;
 pub use razor::*;
 pub use ruby::*;
+pub use script::*;
+pub use shell::*;
 pub use slim::*;
+pub use style::*;
 pub use svelte::*;
 pub use vue::*;
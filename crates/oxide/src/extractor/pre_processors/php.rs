@@ -0,0 +1,101 @@
+use crate::extractor::pre_processors::pre_processor::PreProcessor;
+use crate::extractor::pre_processors::script::Script;
+use bstr::ByteSlice;
+use regex::bytes::Regex;
+use std::sync;
+
+/// `<?php ... ?>` and `<?= ... ?>` tags. The closing tag is optional at the end of a file.
+static PHP_TAG_REGEX: sync::LazyLock<Regex> =
+    sync::LazyLock::new(|| Regex::new(r"<\?(?:php\b|=)([\s\S]*?)(?:\?>|$)").unwrap());
+
+#[derive(Debug, Default)]
+pub struct Php;
+
+impl PreProcessor for Php {
+    fn process(&self, content: &[u8]) -> Vec<u8> {
+        let mut result = content.to_vec();
+
+        // Fast path, plain HTML
+        if !content.contains_str("<?") {
+            return result;
+        }
+
+        // Only keep the string literals inside of PHP tags. Variables like `$classNames`, function
+        // calls, etc. are not classes, but `echo 'p-4'` could be. The HTML around the tags is
+        // scanned as-is.
+        for body in PHP_TAG_REGEX
+            .captures_iter(content)
+            .filter_map(|c| c.get(1))
+        {
+            result[body.range()].copy_from_slice(&Script.process(body.as_bytes()));
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Php;
+    use crate::extractor::pre_processors::pre_processor::PreProcessor;
+
+    #[test]
+    fn test_php_pre_processor() {
+        for (input, expected) in [
+            // Only keep strings inside of PHP tags
+            (
+                r#"<?php $classNames = 'p-4'; ?>"#,
+                r#"<?php               'p-4'  ?>"#,
+            ),
+            // Short echo tags
+            (
+                r#"<div class="<?= $active ? 'bg-red-500' : '' ?>"></div>"#,
+                r#"<div class="<?=           'bg-red-500'   '' ?>"></div>"#,
+            ),
+            // The closing tag is optional at the end of the file
+            (
+                r#"<?php echo render($classNames);"#,
+                r#"<?php                          "#,
+            ),
+            // HTML outside of PHP tags is untouched
+            (
+                r#"<div class="flex underline"></div>"#,
+                r#"<div class="flex underline"></div>"#,
+            ),
+        ] {
+            Php::test(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_php_variables_are_not_extracted() {
+        let input = r#"
+            <?php
+              $classNames = ['font-bold', $isActive ? 'text-red-500' : 'text-gray-500'];
+              $user = get_current_user();
+            ?>
+            <div class="p-4 <?= implode(' ', $classNames) ?>">
+              <?php if ($user): ?>
+                <span class="italic"><?= htmlspecialchars($user->name) ?></span>
+              <?php endif; ?>
+            </div>
+        "#;
+
+        Php::test_extract_contains(
+            input,
+            vec![
+                "p-4",
+                "italic",
+                "font-bold",
+                "text-red-500",
+                "text-gray-500",
+            ],
+        );
+
+        let output = Php.process(input.as_bytes());
+        let output = String::from_utf8_lossy(&output);
+        assert!(!output.contains("classNames"));
+        assert!(!output.contains("isActive"));
+        assert!(!output.contains("get_current_user"));
+    }
+}
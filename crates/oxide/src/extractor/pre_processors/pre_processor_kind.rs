@@ -0,0 +1,107 @@
+use crate::extractor::pre_processors::*;
+use fxhash::FxHashMap;
+
+/// All built-in pre-processors, so that they can be referenced as data. E.g.: when mapping a
+/// custom file extension to an existing pre-processor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PreProcessorKind {
+    /// Not used by default, e.g.: map `html` to this pre-processor to scan the strings inside of
+    /// Alpine's `x-data` objects
+    Alpine,
+    Astro,
+    Blade,
+    Clojure,
+    Haml,
+    Html,
+    Json,
+    Jsx,
+    Liquid,
+    Markdown,
+    Php,
+    /// Not used by default, e.g.: map `properties` to this pre-processor to scan the values of
+    /// `key=value` lines
+    Properties,
+    Pug,
+    Razor,
+    Ruby,
+    Script,
+    /// Not used by default, e.g.: map `sh` to this pre-processor to scan heredocs in scripts
+    Shell,
+    Slim,
+    Svelte,
+    Vue,
+}
+
+/// The extensions that are handled by a pre-processor by default.
+const DEFAULT_EXTENSIONS: &[(&str, PreProcessorKind)] = &[
+    ("astro", PreProcessorKind::Astro),
+    ("blade.php", PreProcessorKind::Blade),
+    ("clj", PreProcessorKind::Clojure),
+    ("cljs", PreProcessorKind::Clojure),
+    ("cljc", PreProcessorKind::Clojure),
+    ("cshtml", PreProcessorKind::Razor),
+    ("razor", PreProcessorKind::Razor),
+    ("haml", PreProcessorKind::Haml),
+    ("html", PreProcessorKind::Html),
+    ("htm", PreProcessorKind::Html),
+    ("json", PreProcessorKind::Json),
+    ("cjs", PreProcessorKind::Jsx),
+    ("js", PreProcessorKind::Jsx),
+    ("jsx", PreProcessorKind::Jsx),
+    ("mjs", PreProcessorKind::Jsx),
+    ("ts", PreProcessorKind::Jsx),
+    ("tsx", PreProcessorKind::Jsx),
+    ("liquid", PreProcessorKind::Liquid),
+    ("md", PreProcessorKind::Markdown),
+    ("php", PreProcessorKind::Php),
+    ("pug", PreProcessorKind::Pug),
+    ("rb", PreProcessorKind::Ruby),
+    ("erb", PreProcessorKind::Ruby),
+    ("slim", PreProcessorKind::Slim),
+    ("svelte", PreProcessorKind::Svelte),
+    ("vue", PreProcessorKind::Vue),
+];
+
+impl PreProcessorKind {
+    /// The pre-processor that handles the given extension by default, if any.
+    pub fn for_extension(extension: &str) -> Option<Self> {
+        DEFAULT_EXTENSIONS
+            .iter()
+            .find(|(x, _)| *x == extension)
+            .map(|(_, kind)| *kind)
+    }
+
+    /// The default extension to pre-processor mapping, which can be modified and passed to the
+    /// scanner.
+    pub fn default_mapping() -> FxHashMap<String, Self> {
+        DEFAULT_EXTENSIONS
+            .iter()
+            .map(|(extension, kind)| (extension.to_string(), *kind))
+            .collect()
+    }
+
+    pub fn process(&self, content: &[u8]) -> Vec<u8> {
+        match self {
+            PreProcessorKind::Alpine => Alpine.process(content),
+            PreProcessorKind::Astro => Astro.process(content),
+            PreProcessorKind::Blade => Blade.process(content),
+            PreProcessorKind::Clojure => Clojure.process(content),
+            PreProcessorKind::Haml => Haml.process(content),
+            PreProcessorKind::Html => Html.process(content),
+            PreProcessorKind::Json => Json.process(content),
+            PreProcessorKind::Jsx => Jsx.process(content),
+            PreProcessorKind::Liquid => Liquid.process(content),
+            PreProcessorKind::Markdown => Markdown.process(content),
+            PreProcessorKind::Php => Php.process(content),
+            PreProcessorKind::Properties => Properties.process(content),
+            PreProcessorKind::Pug => Pug.process(content),
+            PreProcessorKind::Razor => Razor.process(content),
+            PreProcessorKind::Ruby => Ruby.process(content),
+            PreProcessorKind::Script => Script.process(content),
+            PreProcessorKind::Shell => Shell.process(content),
+            PreProcessorKind::Slim => Slim.process(content),
+            PreProcessorKind::Svelte => Svelte.process(content),
+            PreProcessorKind::Vue => Vue.process(content),
+        }
+    }
+}
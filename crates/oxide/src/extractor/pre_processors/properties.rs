@@ -0,0 +1,84 @@
+use crate::extractor::pre_processors::pre_processor::PreProcessor;
+
+/// Only keeps the values of `key=value` lines, e.g.: in `.properties` or `.env` files. Keys,
+/// comments and lines without a value are replaced with spaces.
+#[derive(Debug, Default)]
+pub struct Properties;
+
+impl PreProcessor for Properties {
+    fn process(&self, content: &[u8]) -> Vec<u8> {
+        let mut result: Vec<u8> = content
+            .iter()
+            .map(|x| if x.is_ascii_whitespace() { *x } else { b' ' })
+            .collect();
+
+        let mut start = 0;
+
+        for line in content.split_inclusive(|x| *x == b'\n') {
+            let end = start + line.len();
+            let trimmed = line.trim_ascii_start();
+
+            // Comments, e.g.: `# home.hero.classes=p-8` or `! home.hero.classes=p-8`
+            let is_comment = trimmed.starts_with(b"#") || trimmed.starts_with(b"!");
+
+            // The value starts after the first unescaped separator, e.g.: `a\=b=p-8`
+            //                                                                   ^
+            let separator = line.iter().enumerate().position(|(idx, x)| {
+                matches!(x, b'=' | b':') && (idx == 0 || line[idx - 1] != b'\\')
+            });
+
+            if let (false, Some(separator)) = (is_comment, separator) {
+                result[start + separator + 1..end].copy_from_slice(&line[separator + 1..]);
+            }
+
+            start = end;
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Properties;
+    use crate::extractor::pre_processors::pre_processor::PreProcessor;
+
+    #[test]
+    fn test_properties_pre_processor() {
+        for (input, expected) in [
+            // Only values are kept
+            ("home.hero.classes=p-8", "                  p-8"),
+            ("home.hero.classes: p-8", "                   p-8"),
+            // Escaped separators are part of the key
+            (r"a\=flex=p-8", "        p-8"),
+            // Comments are ignored
+            ("# a=flex\n! b=flex\n", "        \n        \n"),
+            // Lines without a value are ignored
+            ("flex\n", "    \n"),
+            // `.env` style
+            (
+                "export HERO=\"p-8 text-center\"",
+                "            \"p-8 text-center\"",
+            ),
+        ] {
+            Properties::test(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_properties_extraction() {
+        let input = r#"
+            # Classes for the hero section
+            home.hero.classes=p-8 text-center
+            home.hero.title.classes = font-bold
+            feature.underline.enabled=true
+        "#;
+
+        Properties::test_extract_contains(input, vec!["p-8", "text-center", "font-bold"]);
+
+        let output = Properties.process(input.as_bytes());
+        let output = String::from_utf8_lossy(&output);
+        assert!(!output.contains("home"));
+        assert!(!output.contains("underline"));
+    }
+}
@@ -14,6 +14,9 @@ impl PreProcessor for Pug {
         let mut cursor = cursor::Cursor::new(content);
         let mut bracket_stack = BracketStack::default();
 
+        // Whether we are in a class shorthand, e.g.: `.flex`
+        let mut in_class = false;
+
         while cursor.pos < len {
             match cursor.curr {
                 // Only replace `.` with a space if it's not surrounded by numbers. E.g.:
@@ -54,6 +57,32 @@ impl PreProcessor for Pug {
                     } else {
                         result[cursor.pos] = b' ';
                     }
+
+                    in_class = result[cursor.pos] == b' ';
+                }
+
+                // Attributes directly following a class, e.g.: `button.px-2(class=extra)`. The
+                // `(` is not part of the class, but we have to keep `-(` intact for arbitrary
+                // values using CSS variables, e.g.: `w-(--my-width)`, and inside of arbitrary
+                // values, e.g.: `w-[calc(100%-2rem)]`.
+                b'(' if in_class
+                    && bracket_stack.is_empty()
+                    && cursor.prev.is_ascii_alphanumeric() =>
+                {
+                    result[cursor.pos] = b' ';
+                    bracket_stack.push(cursor.curr);
+                }
+
+                // Attributes merged via `&attributes`, e.g.: `div.flex&attributes(attributes)`
+                b'&' if bracket_stack.is_empty()
+                    && content[cursor.pos + 1..].starts_with(b"attributes(") =>
+                {
+                    result[cursor.pos] = b' ';
+                    in_class = false;
+                }
+
+                b' ' | b'\t' | b'\n' | b'\r' => {
+                    in_class = false;
                 }
 
                 b'(' | b'[' | b'{' => {
@@ -95,6 +124,18 @@ mod tests {
             ),
             // Classes in HTML attributes
             (r#"<div id="px-2.5"></div>"#, r#"<div id="px-2.5"></div>"#),
+            // Classes followed by attributes
+            ("button.px-2(class=extra)", "button px-2 class=extra)"),
+            ("div.flex&attributes(attrs)", "div flex attributes(attrs)"),
+            // Arbitrary values using CSS variables
+            (".w-(--my-width)", " w-(--my-width)"),
+            // Arbitrary values containing parentheses
+            ("div.w-[calc(100%-2rem)]", "div w-[calc(100%-2rem)]"),
+            ("div.bg-[url(/a.png)]", "div bg-[url(/a.png)]"),
+            (
+                "div.grid-cols-[repeat(2,1fr)]",
+                "div grid-cols-[repeat(2,1fr)]",
+            ),
         ] {
             Pug::test(input, expected);
         }
@@ -134,4 +175,34 @@ mod tests {
         "#;
         Pug::test_extract_contains(input, vec!["flex", "items-center"]);
     }
+
+    #[test]
+    fn test_mixins() {
+        let input = r#"
+            mixin btn(extra)
+              button.px-2.py-1(class=extra)&attributes(attributes)
+
+            +btn('p-4 font-bold')
+            +btn("bg-red-500")(class="text-white")
+            +btn.mx-auto&attributes({'class': 'underline flex'})
+        "#;
+
+        Pug::test_extract_contains(
+            input,
+            vec![
+                // Mixin definition
+                "px-2",
+                "py-1",
+                // Mixin calls
+                "p-4",
+                "font-bold",
+                "bg-red-500",
+                "text-white",
+                "mx-auto",
+                // Merged attributes
+                "underline",
+                "flex",
+            ],
+        );
+    }
 }
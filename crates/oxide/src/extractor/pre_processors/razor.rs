@@ -1,15 +1,154 @@
 use crate::extractor::pre_processors::pre_processor::PreProcessor;
+use crate::extractor::pre_processors::script::Script;
 use bstr::ByteSlice;
 
+/// Keywords that are followed by C# code in parentheses, e.g.: `@foreach (var item in items)`.
+/// The body in braces is markup again.
+const CONTROL_FLOW_KEYWORDS: &[&[u8]] = &[
+    b"for", b"foreach", b"if", b"lock", b"switch", b"using", b"while",
+];
+
+/// Keywords that are followed by a C# code block, e.g.: `@code { ... }`
+const CODE_BLOCK_KEYWORDS: &[&[u8]] = &[b"code", b"functions"];
+
 #[derive(Debug, Default)]
 pub struct Razor;
 
 impl PreProcessor for Razor {
     fn process(&self, content: &[u8]) -> Vec<u8> {
-        content.replace("@@", " @")
+        let len = content.len();
+        let mut result = content.to_vec();
+        let mut idx = 0;
+
+        while idx < len {
+            if content[idx] != b'@' {
+                idx += 1;
+                continue;
+            }
+
+            let prev = idx.checked_sub(1).map(|idx| content[idx]);
+            let next = content.get(idx + 1).copied();
+
+            match next {
+                // Escaped `@`, e.g.: `@@sm:text-red-500`
+                Some(b'@') => {
+                    result[idx] = b' ';
+                    idx += 2;
+                }
+
+                // Comments, e.g.: `@* ... *@`
+                Some(b'*') => {
+                    let end = content[idx + 2..]
+                        .find(b"*@")
+                        .map_or(len, |end| idx + 2 + end + 2);
+                    result[idx..end].fill(b' ');
+                    idx = end;
+                }
+
+                // Code blocks and explicit expressions, e.g.: `@{ var x = 1; }` or `@(x + 1)`.
+                // Only the strings inside of them can contain classes.
+                Some(b'{') | Some(b'(') => {
+                    let end = code(content, &mut result, idx + 1);
+                    result[idx] = b' ';
+                    idx = end;
+                }
+
+                // Implicit expressions and keywords, e.g.: `@item.Name` or `@foreach (...)`. Email
+                // addresses are not expressions, e.g.: `hello@example.com`.
+                Some(x) if is_identifier_start(x) && !prev.is_some_and(is_identifier) => {
+                    let mut end = idx + 1;
+                    while end < len
+                        && (is_identifier(content[end])
+                            || (content[end] == b'.'
+                                && content
+                                    .get(end + 1)
+                                    .copied()
+                                    .is_some_and(is_identifier_start)))
+                    {
+                        end += 1;
+                    }
+
+                    let word = &content[idx + 1..end];
+                    result[idx..end].fill(b' ');
+
+                    let next = skip_whitespace(content, end);
+                    idx = match content.get(next) {
+                        Some(b'(') if CONTROL_FLOW_KEYWORDS.contains(&word) => {
+                            code(content, &mut result, next)
+                        }
+                        Some(b'{') if CODE_BLOCK_KEYWORDS.contains(&word) => {
+                            code(content, &mut result, next)
+                        }
+                        // Method calls, e.g.: `@Html.Raw(value)`
+                        Some(b'(') if next == end => code(content, &mut result, next),
+                        _ => end,
+                    };
+                }
+
+                _ => idx += 1,
+            }
+        }
+
+        result
     }
 }
 
+// Keep only the strings of the C# code in the brackets starting at `start`, and return the
+// position after the closing bracket.
+fn code(content: &[u8], result: &mut [u8], start: usize) -> usize {
+    let end = closing_bracket(content, start).map_or(content.len(), |end| end + 1);
+    result[start..end].copy_from_slice(&Script.process(&content[start..end]));
+    end
+}
+
+// The position of the bracket that closes the bracket at `start`, skipping brackets inside of
+// strings.
+fn closing_bracket(content: &[u8], start: usize) -> Option<usize> {
+    let open = content[start];
+    let close = if open == b'{' { b'}' } else { b')' };
+
+    let mut depth = 0;
+    let mut quote = None;
+    let mut idx = start;
+
+    while idx < content.len() {
+        match (quote, content[idx]) {
+            (Some(_), b'\\') => idx += 1,
+            (Some(q), x) if x == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'\'' | b'"') => quote = Some(content[idx]),
+            (None, x) if x == open => depth += 1,
+            (None, x) if x == close => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(idx);
+                }
+            }
+            (None, _) => {}
+        }
+
+        idx += 1;
+    }
+
+    None
+}
+
+fn skip_whitespace(content: &[u8], start: usize) -> usize {
+    start
+        + content[start..]
+            .iter()
+            .take_while(|x| x.is_ascii_whitespace())
+            .count()
+}
+
+fn is_identifier_start(x: u8) -> bool {
+    x.is_ascii_alphabetic() || x == b'_'
+}
+
+fn is_identifier(x: u8) -> bool {
+    x.is_ascii_alphanumeric() || x == b'_'
+}
+
 #[cfg(test)]
 mod tests {
     use super::Razor;
@@ -24,4 +163,51 @@ mod tests {
         Razor::test(input, expected);
         Razor::test_extract_contains(input, vec!["@sm:text-red-500"]);
     }
+
+    #[test]
+    fn test_razor_code() {
+        for (input, expected) in [
+            // Code blocks
+            ("@{ var x = 1; }", "               "),
+            (r#"@{ var cls = "p-4"; }"#, r#"             "p-4"   "#),
+            // Explicit expressions
+            (
+                r#"<ul class="@(active ? "font-bold" : "") list">"#,
+                r#"<ul class="           "font-bold"   ""  list">"#,
+            ),
+            // Implicit expressions
+            (
+                "<li>@item.Name, @Html.Raw(value)</li>",
+                "<li>          ,                 </li>",
+            ),
+            // Conditional attributes keep their static classes
+            (r#"<div @class="p-4">"#, r#"<div       ="p-4">"#),
+            // Comments
+            ("@* class=\"flex\" *@", "                  "),
+            // Email addresses
+            ("hello@example.com", "hello@example.com"),
+        ] {
+            Razor::test(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_razor_loops() {
+        let input = r#"
+            @{ var x = 1; }
+            <ul class="@(x > 0 ? "flex" : "hidden")">
+              @foreach (var item in Model.Items) {
+                <li class="text-lg">@item.Name</li>
+              }
+            </ul>
+        "#;
+
+        Razor::test_extract_contains(input, vec!["flex", "hidden", "text-lg"]);
+
+        let output = Razor.process(input.as_bytes());
+        let output = String::from_utf8_lossy(&output);
+        for token in ["var", "foreach", "item", "Model", "Name"] {
+            assert!(!output.contains(token), "{token}");
+        }
+    }
 }
@@ -153,4 +153,37 @@ mod tests {
             Ruby::test_extract_contains(input, expected);
         }
     }
+
+    #[test]
+    fn test_ruby_class_hash_extraction() {
+        for (input, expected) in [
+            // String keys as classes
+            (
+                r#"tag.div(class: { "p-4": active, 'mt-2': other })"#,
+                vec!["p-4", "mt-2"],
+            ),
+            // Multiple classes in a single key
+            (
+                r#"tag.div(class: { "font-bold hover:underline": bold })"#,
+                vec!["font-bold", "hover:underline"],
+            ),
+            // Hash rockets
+            (
+                r#"content_tag :div, class: { "bg-red-500" => error, "text-[#fff]" => true }"#,
+                vec!["bg-red-500", "text-[#fff]"],
+            ),
+            // Hashes inside of arrays
+            (
+                r#"link_to "Home", root_path, class: ["px-2", { "py-1": compact }]"#,
+                vec!["px-2", "py-1"],
+            ),
+            // ERB
+            (
+                r#"<%= render ButtonComponent.new(class: {"rounded-lg": true}) %>"#,
+                vec!["rounded-lg"],
+            ),
+        ] {
+            Ruby::test_extract_contains(input, expected);
+        }
+    }
 }
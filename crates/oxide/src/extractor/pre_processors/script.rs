@@ -0,0 +1,167 @@
+use crate::cursor;
+use crate::extractor::pre_processors::pre_processor::PreProcessor;
+
+/// Pre-processor for JavaScript-like code that is embedded in another language, e.g.: the
+/// `<script>` block of a Vue component.
+///
+/// Only string literals and unquoted object keys are kept, everything else is replaced with
+/// whitespace. This way class strings like `const classes = 'p-4'` and class objects like
+/// `{ flex: true }` are still extracted, but identifiers, imports and comments don't end up as
+/// candidates.
+#[derive(Debug, Default)]
+pub struct Script;
+
+impl PreProcessor for Script {
+    fn process(&self, content: &[u8]) -> Vec<u8> {
+        let len = content.len();
+        let mut result = content.to_vec();
+        let mut cursor = cursor::Cursor::new(content);
+
+        // Brackets that are currently open, used to detect object keys
+        let mut brackets = vec![];
+
+        while cursor.pos < len {
+            match cursor.curr {
+                // Consume strings as-is
+                b'"' | b'\'' | b'`' => {
+                    let quote = cursor.curr;
+                    cursor.advance();
+
+                    while cursor.pos < len {
+                        match cursor.curr {
+                            // Escaped character, skip ahead to the next character
+                            b'\\' => cursor.advance_twice(),
+
+                            // End of the string
+                            _ if cursor.curr == quote => break,
+
+                            // Only template literals can span multiple lines
+                            b'\n' if quote != b'`' => break,
+
+                            // Everything else is valid
+                            _ => cursor.advance(),
+                        };
+                    }
+                }
+
+                // Replace line comments with whitespace
+                b'/' if cursor.next == b'/' => {
+                    while cursor.pos < len && cursor.curr != b'\n' {
+                        result[cursor.pos] = b' ';
+                        cursor.advance();
+                    }
+
+                    continue;
+                }
+
+                // Replace block comments with whitespace, but keep the newlines
+                b'/' if cursor.next == b'*' => {
+                    while cursor.pos < len && !(cursor.curr == b'*' && cursor.next == b'/') {
+                        if cursor.curr != b'\n' {
+                            result[cursor.pos] = b' ';
+                        }
+                        cursor.advance();
+                    }
+
+                    // Closing `*/`
+                    if cursor.pos < len {
+                        result[cursor.pos] = b' ';
+                        cursor.advance();
+                        result[cursor.pos] = b' ';
+                    }
+                }
+
+                // Keep whitespace, so that we still split content on the same lines
+                _ if cursor.curr.is_ascii_whitespace() => {}
+
+                b'(' | b'[' | b'{' => {
+                    brackets.push(cursor.curr);
+                    result[cursor.pos] = b' ';
+                }
+
+                b')' | b']' | b'}' => {
+                    brackets.pop();
+                    result[cursor.pos] = b' ';
+                }
+
+                // Keep unquoted object keys, e.g.: `{ flex: true, hidden: !open }`
+                x if is_identifier_start(x)
+                    && !is_identifier(cursor.prev)
+                    && brackets.last() == Some(&b'{') =>
+                {
+                    let end = cursor.pos
+                        + content[cursor.pos..]
+                            .iter()
+                            .take_while(|x| is_identifier(**x))
+                            .count();
+
+                    let prev = content[..cursor.pos]
+                        .iter()
+                        .rev()
+                        .find(|x| !x.is_ascii_whitespace());
+                    let next = content[end..].iter().find(|x| !x.is_ascii_whitespace());
+
+                    if matches!(prev, Some(b'{' | b',')) && next == Some(&b':') {
+                        cursor.move_to(end);
+                        continue;
+                    }
+
+                    result[cursor.pos] = b' ';
+                }
+
+                // Replace everything else with whitespace
+                _ => {
+                    result[cursor.pos] = b' ';
+                }
+            };
+
+            cursor.advance();
+        }
+
+        result
+    }
+}
+
+fn is_identifier_start(x: u8) -> bool {
+    x.is_ascii_alphabetic() || x == b'_' || x == b'$'
+}
+
+fn is_identifier(x: u8) -> bool {
+    x.is_ascii_alphanumeric() || x == b'_' || x == b'$'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Script;
+    use crate::extractor::pre_processors::pre_processor::PreProcessor;
+
+    #[test]
+    fn test_script_pre_processor() {
+        for (input, expected) in [
+            // Identifiers are removed, strings are kept
+            ("const cls = 'p-4'", "            'p-4'"),
+            (
+                r#"let a = "flex", b = `px-2.5`"#,
+                r#"        "flex"      `px-2.5`"#,
+            ),
+            // Escaped quotes are part of the string
+            (r#"x('it\'s flex')"#, r#"  'it\'s flex' "#),
+            // Comments are removed
+            ("a // 'p-4'", "          "),
+            ("a /* 'p-4' */ b", "               "),
+            // Unquoted object keys are kept
+            (
+                "const classes = { flex: true, hidden: !open }",
+                "                  flex        hidden         ",
+            ),
+            ("x({ a: b ? c : d })", "    a              "),
+        ] {
+            Script::test(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_multi_line_comments_keep_newlines() {
+        Script::test("/*\nflex\n*/ 'p-4'", "  \n    \n   'p-4'");
+    }
+}
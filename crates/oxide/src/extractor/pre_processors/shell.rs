@@ -0,0 +1,101 @@
+use crate::extractor::pre_processors::pre_processor::PreProcessor;
+use regex::bytes::Regex;
+use std::sync;
+
+static HEREDOC_REGEX: sync::LazyLock<Regex> = sync::LazyLock::new(|| {
+    Regex::new(r#"(?:^|[^<])<<-?\s*['"]?([A-Za-z_][A-Za-z0-9_]*)['"]?"#).unwrap()
+});
+
+/// Only keeps the contents of heredocs, e.g.: `cat <<EOF ... EOF`. Shell commands outside of
+/// heredocs are replaced with spaces.
+#[derive(Debug, Default)]
+pub struct Shell;
+
+impl PreProcessor for Shell {
+    fn process(&self, content: &[u8]) -> Vec<u8> {
+        let mut result: Vec<u8> = content
+            .iter()
+            .map(|x| if x.is_ascii_whitespace() { *x } else { b' ' })
+            .collect();
+
+        // The delimiter of the heredoc we are currently in, e.g.: `EOF`
+        let mut delimiter: Option<&[u8]> = None;
+        let mut start = 0;
+
+        for line in content.split_inclusive(|x| *x == b'\n') {
+            let end = start + line.len();
+
+            match delimiter {
+                Some(current) if line.trim_ascii() == current => {
+                    delimiter = None;
+                }
+
+                Some(_) => {
+                    result[start..end].copy_from_slice(line);
+                }
+
+                None => {
+                    delimiter = HEREDOC_REGEX
+                        .captures(line)
+                        .and_then(|captures| captures.get(1))
+                        .map(|delimiter| delimiter.as_bytes());
+                }
+            }
+
+            start = end;
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Shell;
+    use crate::extractor::pre_processors::pre_processor::PreProcessor;
+
+    #[test]
+    fn test_shell_pre_processor() {
+        for (input, expected) in [
+            // Commands outside of heredocs are ignored
+            ("echo flex", "         "),
+            // Heredocs
+            (
+                "cat <<EOF\n<div class=\"p-4\">\nEOF\necho flex",
+                "         \n<div class=\"p-4\">\n   \n         ",
+            ),
+            // Quoted and indented delimiters
+            (
+                "cat <<-'HTML'\n\t<b class=\"flex\">\n\tHTML\n",
+                "             \n\t<b class=\"flex\">\n\t    \n",
+            ),
+            // Here-strings are not heredocs
+            ("cat <<<\"EOF\"\nflex\n", "            \n    \n"),
+        ] {
+            Shell::test(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_shell_heredoc_extraction() {
+        let input = r#"
+            #!/usr/bin/env bash
+            mkdir -p dist
+
+            cat > dist/index.html <<EOF
+            <div class="flex items-center p-4">
+              <span class="text-${COLOR}-500 font-bold">$TITLE</span>
+            </div>
+            EOF
+
+            echo "underline"
+        "#;
+
+        Shell::test_extract_contains(input, vec!["flex", "items-center", "p-4", "font-bold"]);
+
+        let output = Shell.process(input.as_bytes());
+        let output = String::from_utf8_lossy(&output);
+        assert!(!output.contains("mkdir"));
+        assert!(!output.contains("underline"));
+    }
+}
@@ -2,6 +2,7 @@ use crate::cursor;
 use crate::extractor::bracket_stack::BracketStack;
 use crate::extractor::machine::{Machine, MachineState};
 use crate::extractor::pre_processors::pre_processor::PreProcessor;
+use crate::extractor::pre_processors::script::Script;
 use crate::extractor::variant_machine::VariantMachine;
 
 #[derive(Debug, Default)]
@@ -16,6 +17,17 @@ impl PreProcessor for Slim {
 
         while cursor.pos < len {
             match cursor.curr {
+                // Ruby interpolation in attributes and text, e.g.: `#{user.name}`. Only the
+                // strings inside of it can contain classes, e.g.: `#{active ? 'font-bold' : ''}`.
+                // Escaped interpolation is kept as-is, e.g.: `\#{body_classes}`.
+                b'#' if cursor.next == b'{' && cursor.prev != b'\\' => {
+                    let end = closing_brace(content, cursor.pos + 2);
+                    let range = cursor.pos..(end + 1).min(len);
+
+                    result[range.clone()].copy_from_slice(&Script.process(&content[range]));
+                    cursor.move_to(end);
+                }
+
                 // Only replace `.` with a space if it's not surrounded by numbers. E.g.:
                 //
                 // ```diff
@@ -116,6 +128,31 @@ impl PreProcessor for Slim {
     }
 }
 
+// The position of the `}` that closes the interpolation starting at `start`, skipping braces inside
+// of strings. The end of the content is used for unbalanced interpolation.
+fn closing_brace(content: &[u8], start: usize) -> usize {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut idx = start;
+
+    while idx < content.len() {
+        match (quote, content[idx]) {
+            (Some(_), b'\\') => idx += 1,
+            (Some(q), x) if x == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'\'' | b'"') => quote = Some(content[idx]),
+            (None, b'{') => depth += 1,
+            (None, b'}') if depth == 0 => return idx,
+            (None, b'}') => depth -= 1,
+            (None, _) => {}
+        }
+
+        idx += 1;
+    }
+
+    content.len()
+}
+
 #[cfg(test)]
 mod tests {
     use super::Slim;
@@ -281,4 +318,50 @@ mod tests {
         "#;
         Slim::test_extract_contains(input, vec!["flex", "items-center"]);
     }
+
+    #[test]
+    fn test_ruby_interpolation() {
+        for (input, expected) in [
+            // Attributes
+            (
+                r##"div class="#{dynamic} p-4""##,
+                r##"div class="           p-4""##,
+            ),
+            // Text
+            (
+                "p Hello #{user.name} and #{count}",
+                "p Hello              and         ",
+            ),
+            // Strings inside of the interpolation are kept
+            (
+                "div class=\"#{active ? 'font-bold' : ''} p-4\"",
+                "div class=\"           'font-bold'   ''  p-4\"",
+            ),
+            // Nested braces
+            (
+                "p #{items.map { |x| x.name }.join} flex",
+                "p                                  flex",
+            ),
+        ] {
+            Slim::test(input, expected);
+        }
+    }
+
+    #[test]
+    fn test_ruby_interpolation_extraction() {
+        let input = r##"
+            .foo.bar.px-2.5 class="#{dynamic} p-4"
+              | Hello #{user.name}, #{active ? 'font-bold' : 'italic'}
+        "##;
+
+        Slim::test_extract_contains(
+            input,
+            vec!["foo", "bar", "px-2.5", "p-4", "font-bold", "italic"],
+        );
+
+        let output = Slim.process(input.as_bytes());
+        let output = String::from_utf8_lossy(&output);
+        assert!(!output.contains("dynamic"));
+        assert!(!output.contains("user"));
+    }
 }
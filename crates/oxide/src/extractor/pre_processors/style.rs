@@ -0,0 +1,74 @@
+use crate::extractor::pre_processors::pre_processor::PreProcessor;
+
+/// Pre-processor for CSS that is embedded in another language, e.g.: the `<style>` block of a Vue
+/// component.
+///
+/// Only the utilities of `@apply` rules are kept, everything else is replaced with whitespace.
+/// This way `@apply p-4;` is still extracted, but selectors like `.btn:hover` and declarations
+/// don't end up as candidates.
+#[derive(Debug, Default)]
+pub struct Style;
+
+impl PreProcessor for Style {
+    fn process(&self, content: &[u8]) -> Vec<u8> {
+        let len = content.len();
+        let mut result = content.to_vec();
+        let mut idx = 0;
+
+        while idx < len {
+            let is_apply = content[idx..].starts_with(b"@apply")
+                && content
+                    .get(idx + 6)
+                    .is_some_and(|x| x.is_ascii_whitespace());
+
+            if !is_apply {
+                // Keep whitespace, so that we still split content on the same lines
+                if !content[idx].is_ascii_whitespace() {
+                    result[idx] = b' ';
+                }
+
+                idx += 1;
+                continue;
+            }
+
+            // Drop the `@apply` itself
+            result[idx..idx + 6].fill(b' ');
+            idx += 6;
+
+            // Keep the utilities until the end of the rule
+            while idx < len && content[idx] != b';' && content[idx] != b'}' {
+                idx += 1;
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Style;
+    use crate::extractor::pre_processors::pre_processor::PreProcessor;
+
+    #[test]
+    fn test_style_pre_processor() {
+        for (input, expected) in [
+            // Only keep the utilities of `@apply` rules
+            (
+                ".card { @apply p-4 hover:bg-gray-100; }",
+                "               p-4 hover:bg-gray-100   ",
+            ),
+            (
+                ".btn { @apply\n  font-bold\n  underline }",
+                "             \n  font-bold\n  underline  ",
+            ),
+            // Selectors and declarations are dropped
+            (
+                ".btn-primary:hover { color: red; }",
+                "                                  ",
+            ),
+        ] {
+            Style::test(input, expected);
+        }
+    }
+}
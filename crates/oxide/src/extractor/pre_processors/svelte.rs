@@ -1,12 +1,26 @@
 use crate::extractor::pre_processors::pre_processor::PreProcessor;
+use crate::extractor::pre_processors::style::Style;
 use bstr::ByteSlice;
+use regex::bytes::Regex;
+use std::sync;
+
+static STYLE_REGEX: sync::LazyLock<Regex> =
+    sync::LazyLock::new(|| Regex::new(r#"<style\b[^>]*>([\s\S]*?)<\/style>"#).unwrap());
 
 #[derive(Debug, Default)]
 pub struct Svelte;
 
 impl PreProcessor for Svelte {
     fn process(&self, content: &[u8]) -> Vec<u8> {
-        content
+        let mut result = content.to_vec();
+
+        // Only keep the utilities of `@apply` rules inside of `<style>` blocks. Selectors are not
+        // classes we have to generate.
+        for body in STYLE_REGEX.captures_iter(content).filter_map(|c| c.get(1)) {
+            result[body.range()].copy_from_slice(&Style.process(body.as_bytes()));
+        }
+
+        result
             .replace(" class:", " class ")
             .replace("\tclass:", " class ")
             .replace("\nclass:", " class ")
@@ -40,4 +54,54 @@ mod tests {
             Svelte::test(input, expected);
         }
     }
+
+    #[test]
+    fn test_svelte_class_objects_and_html_blocks() {
+        let input = r#"
+            <div class={{ 'p-4': big, "font-bold": bold, underline }}></div>
+            <div class={['flex', active && 'gap-2']}></div>
+            <div class:bg-red-500={error} class:hover:text-white={error}></div>
+            {@html `<div class="flex-col">Hello</div>`}
+            {@html '<span class="text-sm">World</span>'}
+        "#;
+
+        Svelte::test_extract_contains(
+            input,
+            vec![
+                // Object and array syntax
+                "p-4",
+                "font-bold",
+                "underline",
+                "flex",
+                "gap-2",
+                // Class directives
+                "bg-red-500",
+                "hover:text-white",
+                // Markup in `{@html}` blocks
+                "flex-col",
+                "text-sm",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_svelte_style_apply() {
+        let input = r#"<div class="flex"></div>
+<style>
+.card {
+  @apply p-4 rounded-lg hover:bg-gray-100;
+}
+.btn-primary:hover { color: red; }
+</style>"#;
+
+        Svelte::test_extract_contains(
+            input,
+            vec!["flex", "p-4", "rounded-lg", "hover:bg-gray-100"],
+        );
+
+        let output = Svelte.process(input.as_bytes());
+        let output = String::from_utf8_lossy(&output);
+        assert!(!output.contains("card"));
+        assert!(!output.contains("btn-primary"));
+    }
 }
@@ -1,4 +1,6 @@
 use crate::extractor::pre_processors::pre_processor::PreProcessor;
+use crate::extractor::pre_processors::script::Script;
+use crate::extractor::pre_processors::style::Style;
 use crate::pre_process_input;
 use bstr::ByteSlice;
 use regex::Regex;
@@ -8,6 +10,12 @@ static TEMPLATE_REGEX: sync::LazyLock<Regex> = sync::LazyLock::new(|| {
     Regex::new(r#"<template lang=['"]([^"']*)['"]>([\s\S]*)<\/template>"#).unwrap()
 });
 
+static SCRIPT_REGEX: sync::LazyLock<Regex> =
+    sync::LazyLock::new(|| Regex::new(r#"<script\b[^>]*>([\s\S]*?)<\/script>"#).unwrap());
+
+static STYLE_REGEX: sync::LazyLock<Regex> =
+    sync::LazyLock::new(|| Regex::new(r#"<style\b[^>]*>([\s\S]*?)<\/style>"#).unwrap());
+
 #[derive(Debug, Default)]
 pub struct Vue;
 
@@ -16,6 +24,25 @@ impl PreProcessor for Vue {
         let mut result = content.to_vec();
 
         let content_as_str = std::str::from_utf8(content).unwrap();
+
+        // Only keep the string literals inside of `<script>` blocks. Identifiers, imports, etc.
+        // are not classes, but `const classes = 'p-4'` could be.
+        for body in SCRIPT_REGEX
+            .captures_iter(content_as_str)
+            .filter_map(|c| c.get(1))
+        {
+            result[body.range()].copy_from_slice(&Script.process(body.as_str().as_bytes()));
+        }
+
+        // Only keep the utilities of `@apply` rules inside of `<style>` blocks. Selectors are not
+        // classes we have to generate.
+        for body in STYLE_REGEX
+            .captures_iter(content_as_str)
+            .filter_map(|c| c.get(1))
+        {
+            result[body.range()].copy_from_slice(&Style.process(body.as_str().as_bytes()));
+        }
+
         for (_, [lang, body]) in TEMPLATE_REGEX
             .captures_iter(content_as_str)
             .map(|c| c.extract())
@@ -43,4 +70,74 @@ mod tests {
 
         Vue::test_extract_contains(input, vec!["bg-neutral-900", "text-red-500"]);
     }
+
+    #[test]
+    fn test_vue_script_setup() {
+        let input = r#"<script setup>
+import { computed } from 'vue'
+const classes = 'p-4 mt-2'
+</script>
+<template>
+  <div :class="[classes, { 'font-bold': active }]" class="flex"></div>
+</template>"#;
+
+        let expected = r#"<script setup>
+                         'vue'
+                'p-4 mt-2'
+</script>
+<template>
+  <div :class="[classes, { 'font-bold': active }]" class="flex"></div>
+</template>"#;
+
+        Vue::test(input, expected);
+        Vue::test_extract_contains(input, vec!["p-4", "mt-2", "font-bold", "flex"]);
+    }
+
+    #[test]
+    fn test_vue_script_class_object() {
+        let input = r#"<script setup>
+const classes = { flex: true, hidden: !open }
+</script>
+<template>
+  <div :class="classes"></div>
+</template>"#;
+
+        Vue::test_extract_contains(input, vec!["flex", "hidden"]);
+    }
+
+    #[test]
+    fn test_vue_define_props_default_classes() {
+        let input = r#"<script setup lang="ts">
+const props = defineProps({
+  class: { type: String, default: 'p-4 rounded-lg' },
+  variant: { type: String, default: "primary" },
+})
+</script>
+<template>
+  <div :class="props.class"></div>
+</template>"#;
+
+        Vue::test_extract_contains(input, vec!["p-4", "rounded-lg"]);
+    }
+
+    #[test]
+    fn test_vue_style_apply() {
+        let input = r#"<template><div class="flex"></div></template>
+<style lang="postcss">
+.card {
+  @apply p-4 rounded-lg hover:bg-gray-100;
+}
+.btn-primary:hover { color: red; }
+</style>"#;
+
+        Vue::test_extract_contains(
+            input,
+            vec!["flex", "p-4", "rounded-lg", "hover:bg-gray-100"],
+        );
+
+        let output = Vue.process(input.as_bytes());
+        let output = String::from_utf8_lossy(&output);
+        assert!(!output.contains("card"));
+        assert!(!output.contains("btn-primary"));
+    }
 }
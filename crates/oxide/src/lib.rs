@@ -1,26 +1,36 @@
 use crate::glob::hoist_static_glob_parts;
 use crate::scanner::allowed_paths::resolve_paths;
-use crate::scanner::detect_sources::DetectSources;
+use crate::scanner::detect_sources::{DetectOptions, DetectSources};
 use bexpand::Expression;
 use bstr::ByteSlice;
+use extractor::pre_processors::{JsonKeyPaths, PreProcessor, PreProcessorKind};
 use extractor::{Extracted, Extractor};
 use fast_glob::glob_match;
-use fxhash::{FxHashMap, FxHashSet};
+use fxhash::{FxBuildHasher, FxHashMap, FxHashSet};
 use glob::optimize_patterns;
-use paths::Path;
+use noise_filter::NoiseFilter;
+use paths::{Path, PathRemap};
 use rayon::prelude::*;
-use scanner::allowed_paths::read_dir;
+use scanner::allowed_paths::{
+    has_any_extension, is_allowed_content_path, is_declaration_file, is_file_or_symlinked_file,
+    is_generated_css, matches_content_signature, read_dir_with, PathFilter, WalkOptions,
+};
 use std::borrow::Cow;
+use std::fmt;
 use std::fs;
+use std::hash::BuildHasher;
+use std::ops::ControlFlow;
 use std::path::PathBuf;
 use std::sync;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use tracing::event;
 
+pub mod css_layers;
 pub mod cursor;
 pub mod extractor;
 pub mod fast_skip;
 pub mod glob;
+pub mod noise_filter;
 pub mod paths;
 pub mod scanner;
 pub mod throughput;
@@ -45,14 +55,305 @@ fn init_tracing() {
 pub enum ChangedContent<'a> {
     File(PathBuf, Cow<'a, str>),
     Content(String, Cow<'a, str>),
+    /// Content that concatenates multiple logical sources, e.g.: a build manifest. The content is
+    /// split on the separator (2nd field) and the rest of the line after each separator is used as
+    /// the name of the segment for provenance, e.g.: `/* @source */ src/index.html`.
+    Concatenated(String, Cow<'a, str>, Cow<'a, str>),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ScanOptions {
-    /// Base path to start scanning from
+    /// Directory that relative source bases are resolved against, see [Scanner::set_base].
+    /// Defaults to the current working directory.
     pub base: Option<String>,
     /// Glob sources
     pub sources: Vec<GlobEntry>,
+    /// Called every time a file has been processed
+    pub on_progress: Option<ProgressCallback>,
+    /// Rewrite the prefix of the paths returned by `get_files` and `get_globs`
+    pub path_remap: Option<PathRemap>,
+    /// Extensions that are never picked up by auto source detection, e.g.: `["md"]`
+    pub excluded_extensions: Vec<String>,
+    /// Track which files contributed which candidates, see [Scanner::source_for_candidate]
+    pub provenance: bool,
+    /// Use `/` as the path separator in `get_files`, `get_globs` and `get_dirs` on all platforms
+    pub normalize_separators: bool,
+    /// Path to a file with candidates that should never be returned, one candidate per line
+    pub blocklist_file: Option<PathBuf>,
+    /// Hash the contents of recently modified files, to detect changes that happen within the
+    /// resolution of the file system's modification time
+    pub content_hash: bool,
+    /// Keep track of the order in which candidates were found, see [Scanner::checkpoint]
+    pub checkpoints: bool,
+    /// Maximum amount of files to scan. When more files are found, nothing is scanned at all, see
+    /// [Scanner::file_limit_exceeded]
+    pub max_files: Option<usize>,
+    /// Drop candidates that are most likely not utilities, e.g.: `useState`
+    pub noise_filter: Option<NoiseFilter>,
+    /// Experimental: also scan extension-less files starting with any of these signatures during
+    /// auto source detection, e.g.: `["<!DOCTYPE html>"]`
+    pub content_signatures: Vec<String>,
+    /// Sort all files, directories and globs, so the output is reproducible across runs and
+    /// platforms, e.g.: for snapshot tests
+    pub deterministic: bool,
+    /// Keep a log of what happened during the last scan, see [Scanner::last_scan_log]
+    pub verbose: bool,
+    /// Files that are generated from the candidates, e.g.: a `safelist.html`. These files are
+    /// never scanned, to avoid feeding the output back into the next scan.
+    pub generated_outputs: Vec<PathBuf>,
+    /// Don't skip known ignored files, extensions and binary files during auto source detection,
+    /// e.g.: `package-lock.json`. Only `.gitignore` files and the `excluded_extensions` apply.
+    pub no_default_ignores: bool,
+    /// Extension used to pick the pre-processor for files without an extension, e.g.: `"pug"`
+    pub default_extension: Option<String>,
+    /// Directory that contains the project. Git repositories and `.gitignore` files outside of
+    /// this directory are not considered, e.g.: in nested repositories.
+    pub project_root: Option<PathBuf>,
+    /// Honor `.npmignore` files next to `.gitignore` files, e.g.: when scanning a published
+    /// package to only scan the files it ships
+    pub npmignore: bool,
+    /// Merge arbitrary values that only differ in the whitespace around commas, e.g.:
+    /// `grid-cols-[minmax(0,_1fr)]` is returned as `grid-cols-[minmax(0,1fr)]`
+    pub canonicalize_arbitrary_values: bool,
+    /// Custom inclusion logic on top of the existing ignore rules. Files and directories are
+    /// skipped when the filter returns `false`.
+    pub path_filter: Option<PathFilter>,
+    /// Only scan hidden files and directories inside of these directories, e.g.: the directory of
+    /// an `@source './config' { hidden: true }`. By default hidden files are scanned everywhere.
+    pub hidden_sources: Option<Vec<PathBuf>>,
+    /// Don't generate globs for the directories found by auto source detection. Every source is
+    /// returned from `get_globs` as-is instead, e.g.: `src/**/*` instead of `src/*/*.{html,js}`.
+    pub no_auto_detect_globs: bool,
+    /// Scan CSS files generated by Tailwind CSS as well. These are skipped by default, to avoid
+    /// feeding the output back into the next scan.
+    pub scan_generated_css: bool,
+    /// Lowercase all candidates, so that candidates that only differ in casing are merged, e.g.:
+    /// `Flex` and `flex`. Utilities are case-sensitive, so this is only useful for reporting.
+    pub lowercase_candidates: bool,
+    /// Don't skip TypeScript declaration files and source maps during auto source detection, e.g.:
+    /// `index.d.ts` or `app.js.map`
+    pub include_declaration_files: bool,
+    /// Only pick up files with these extensions during auto source detection, e.g.:
+    /// `["html", "tsx"]`. Explicit sources are still scanned regardless of their extension.
+    pub only_extensions: Option<Vec<String>>,
+    /// Directory that contains the `.git` directory, e.g.: when it's already known because
+    /// scanners are recreated in a watch loop. This skips looking for it in all parent
+    /// directories.
+    pub git_root: Option<PathBuf>,
+    /// Only scan the strings under these key paths in `.json` files, e.g.:
+    /// `["components.*.className"]`. See [JsonKeyPaths] for the syntax.
+    pub json_key_paths: Option<Vec<String>>,
+}
+
+/// Something that happened during a scan, see [Scanner::last_scan_log].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanEvent {
+    /// A directory that is used as a root for auto source detection
+    Root(PathBuf),
+    /// All sources were resolved for the first time
+    SourcesResolved {
+        files: usize,
+        globs: usize,
+        dirs: usize,
+    },
+    /// The sources resolved to more files than allowed by the `max_files` option
+    FileLimitExceeded(usize),
+    /// Files and directories that were added since the previous scan
+    NewFiles(usize),
+    /// Files that had to be read, because they are new or changed since the previous scan
+    FilesRead(usize),
+    /// Candidates that were not known before this scan
+    NewCandidates(usize),
+}
+
+/// Errors that are reported by [Scanner::try_with_options] instead of being ignored.
+#[derive(Debug)]
+pub enum ScannerError {
+    /// The pattern of a source can't be parsed, e.g.: `src/**/*.{html,js`
+    InvalidPattern(String),
+    /// The base path of a source doesn't exist
+    MissingBase(String),
+    /// The `blocklist_file` can't be read
+    Blocklist(std::io::Error),
+    /// The blocking task of an async scan was cancelled before it completed
+    Cancelled,
+}
+
+impl fmt::Display for ScannerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScannerError::InvalidPattern(pattern) => write!(f, "Invalid source pattern: {pattern}"),
+            ScannerError::MissingBase(base) => write!(f, "Source base does not exist: {base}"),
+            ScannerError::Blocklist(e) => write!(f, "Failed to read blocklist: {e}"),
+            ScannerError::Cancelled => write!(f, "Scan was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for ScannerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ScannerError::Blocklist(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Marker of the candidates that were known at a certain point in time, see
+/// [Scanner::checkpoint].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckpointId(usize);
+
+/// The candidates that were added and removed by [Scanner::apply_changes], sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanDelta {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// The directories that were completely scanned by [Scanner::scan_resumable], and the candidates
+/// that were found in them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResumeToken {
+    completed_dirs: FxHashSet<PathBuf>,
+    candidates: FxHashSet<String>,
+}
+
+impl ResumeToken {
+    /// Encode the token as text, e.g.: to persist it between runs. Every line is either a
+    /// completed directory (`d <path>`) or a candidate (`c <candidate>`). Paths are
+    /// percent-encoded, so that newlines and non UTF-8 paths survive the round trip.
+    pub fn encode(&self) -> String {
+        let mut lines: Vec<String> = self
+            .completed_dirs
+            .iter()
+            .map(|dir| format!("d {}", encode_path(dir)))
+            .chain(
+                self.candidates
+                    .iter()
+                    .map(|candidate| format!("c {candidate}")),
+            )
+            .collect();
+
+        lines.sort_unstable();
+        lines.join("\n")
+    }
+
+    /// Decode a token that was created by [ResumeToken::encode]. Unknown lines are ignored.
+    pub fn decode(token: &str) -> Self {
+        let mut result = Self::default();
+
+        for line in token.lines() {
+            match line.split_once(' ') {
+                Some(("d", dir)) => {
+                    if let Some(dir) = decode_path(dir) {
+                        result.completed_dirs.insert(dir);
+                    }
+                }
+                Some(("c", candidate)) => {
+                    result.candidates.insert(candidate.to_owned());
+                }
+                _ => {}
+            }
+        }
+
+        result
+    }
+}
+
+// Percent-encode `%`, control characters and all non-ASCII bytes of a path
+fn encode_path(path: &std::path::Path) -> String {
+    let mut result = String::new();
+
+    for x in path.as_os_str().as_encoded_bytes() {
+        match x {
+            b'%' => result.push_str("%25"),
+            x if x.is_ascii_control() || !x.is_ascii() => result.push_str(&format!("%{x:02X}")),
+            x => result.push(*x as char),
+        }
+    }
+
+    result
+}
+
+// Decode a path that was encoded by `encode_path`. Paths that can't be represented on the current
+// platform are dropped.
+fn decode_path(encoded: &str) -> Option<PathBuf> {
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut rest = encoded.as_bytes();
+
+    while let Some((x, tail)) = rest.split_first() {
+        match x {
+            b'%' => {
+                let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+                rest = &tail[2..];
+            }
+            x => {
+                bytes.push(*x);
+                rest = tail;
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        Some(PathBuf::from(std::ffi::OsStr::from_bytes(&bytes)))
+    }
+
+    #[cfg(not(unix))]
+    {
+        String::from_utf8(bytes).ok().map(PathBuf::from)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanProgress {
+    /// Amount of files that have been processed so far
+    pub done: usize,
+    /// Amount of files that are being processed in the current batch
+    pub total: usize,
+}
+
+/// Callback that receives a [ScanProgress] every time a file has been processed.
+///
+/// Files are processed in parallel, so the callback can be called from multiple threads and the
+/// calls can arrive out of order. The `done` counter itself is guaranteed to be unique for every
+/// call.
+#[derive(Clone)]
+pub struct ProgressCallback(sync::Arc<dyn Fn(ScanProgress) + Send + Sync>);
+
+impl ProgressCallback {
+    pub fn new(callback: impl Fn(ScanProgress) + Send + Sync + 'static) -> Self {
+        Self(sync::Arc::new(callback))
+    }
+
+    fn call(&self, progress: ScanProgress) {
+        (self.0)(progress)
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ProgressCallback").finish()
+    }
+}
+
+/// Reads the content of files before they are pre-processed, see [Scanner::set_content_reader].
+/// E.g.: to only return the relevant part of a bundle.
+pub trait ContentReader: fmt::Debug + Send + Sync {
+    fn read(&self, path: &std::path::Path) -> std::io::Result<Vec<u8>>;
+}
+
+/// Reads files from the file system as-is. This is the default [ContentReader].
+#[derive(Debug, Default)]
+pub struct FsContentReader;
+
+impl ContentReader for FsContentReader {
+    fn read(&self, path: &std::path::Path) -> std::io::Result<Vec<u8>> {
+        fs::read(path)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -62,6 +363,102 @@ pub struct ScanResult {
     pub globs: Vec<GlobEntry>,
 }
 
+/// Mapping of file extensions to the pre-processor that should be used for them. Defaults to
+/// [PreProcessorKind::default_mapping], so a `Scanner::default()` pre-processes files as well.
+#[derive(Debug, Clone)]
+struct PreProcessors {
+    mapping: FxHashMap<String, PreProcessorKind>,
+
+    /// Used for `.json` files instead of the mapping, based on the `json_key_paths` option
+    json_key_paths: Option<JsonKeyPaths>,
+}
+
+impl Default for PreProcessors {
+    fn default() -> Self {
+        Self {
+            mapping: PreProcessorKind::default_mapping(),
+            json_key_paths: None,
+        }
+    }
+}
+
+impl PreProcessors {
+    fn process(&self, content: &[u8], extension: &str) -> Vec<u8> {
+        match &self.json_key_paths {
+            Some(json_key_paths) if extension == "json" => json_key_paths.process(content),
+            _ => pre_process_input_with(content, extension, &self.mapping),
+        }
+    }
+}
+
+/// Modification times are only stored with a limited precision, e.g.: 2 seconds on FAT file
+/// systems. Writes within this window don't necessarily change the modification time.
+const MTIME_RESOLUTION: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FileState {
+    mtime: SystemTime,
+    size: u64,
+
+    /// Hash of the contents, only computed when the file was modified within the
+    /// [MTIME_RESOLUTION] and the `content_hash` option is enabled.
+    hash: Option<u64>,
+}
+
+impl FileState {
+    fn read(path: &std::path::Path, content_hash: bool) -> Self {
+        let now = SystemTime::now();
+        let metadata = fs::metadata(path).ok();
+
+        let mtime = metadata
+            .as_ref()
+            .and_then(|m| m.modified().ok())
+            .unwrap_or(now);
+        let size = metadata.map(|m| m.len()).unwrap_or_default();
+
+        let is_recent = now
+            .duration_since(mtime)
+            .map_or(true, |age| age < MTIME_RESOLUTION);
+
+        let hash = if content_hash && is_recent {
+            fs::read(path)
+                .ok()
+                .map(|content| FxBuildHasher.hash_one(&content))
+        } else {
+            None
+        };
+
+        Self { mtime, size, hash }
+    }
+
+    fn has_changed(&self, current: &FileState) -> bool {
+        if self.mtime != current.mtime || self.size != current.size {
+            return true;
+        }
+
+        // Same modification time and size, the hash is the only way to know whether the file
+        // was written again within the same tick.
+        matches!((self.hash, current.hash), (Some(a), Some(b)) if a != b)
+    }
+}
+
+// Everything that was resolved for a set of sources.
+#[derive(Default)]
+struct ResolvedSources {
+    files: Vec<PathBuf>,
+    globs: Vec<GlobEntry>,
+    dirs: Vec<PathBuf>,
+
+    /// Full glob patterns of the sources that are not using auto source detection
+    patterns: Vec<String>,
+
+    /// Directories that are used for auto source detection
+    roots: Vec<PathBuf>,
+
+    /// More files were found than allowed by the `max_files` option, nothing else is resolved
+    limit_exceeded: bool,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct GlobEntry {
     pub base: String,
@@ -70,8 +467,8 @@ pub struct GlobEntry {
 
 #[derive(Debug, Clone, Default)]
 pub struct Scanner {
-    /// Glob sources
-    sources: Option<Vec<GlobEntry>>,
+    /// Options used to configure the scanner, this also contains the glob sources
+    options: ScanOptions,
 
     /// Scanner is ready to scan. We delay the file system traversal for detecting all files until
     /// we actually need them.
@@ -86,24 +483,203 @@ pub struct Scanner {
     /// All generated globs
     globs: Vec<GlobEntry>,
 
-    /// Track file modification times
+    /// Full glob patterns of explicit sources, e.g.: `/my-project/**/*.styl`. New files matching
+    /// these patterns are allowed, even if their extension is ignored by default.
+    explicit_patterns: Vec<String>,
+
+    /// Track directory modification times
     mtimes: FxHashMap<PathBuf, SystemTime>,
 
+    /// When the file system was last checked for new files
+    last_checked: Option<SystemTime>,
+
+    /// Track the state of files to know whether they changed since the last scan
+    file_states: FxHashMap<PathBuf, FileState>,
+
     /// Track unique set of candidates
     candidates: FxHashSet<String>,
+
+    /// All files that currently contain a given candidate. Only tracked when the `provenance`
+    /// option is enabled.
+    sources_by_candidate: FxHashMap<String, FxHashSet<PathBuf>>,
+
+    /// All candidates found in a given file, used to prune `sources_by_candidate` when the file
+    /// changes.
+    candidates_by_source: FxHashMap<PathBuf, Vec<String>>,
+
+    /// Mapping of file extensions to the pre-processor that should be used for them
+    pre_processors: PreProcessors,
+
+    /// Candidates that should never be returned, loaded from the `blocklist_file` option
+    blocklist: FxHashSet<String>,
+
+    /// Canonical paths of the `generated_outputs` option
+    generated_outputs: FxHashSet<PathBuf>,
+
+    /// Which ignore files are used while walking directories, based on the `project_root` and
+    /// `npmignore` options
+    walk_options: WalkOptions,
+
+    /// All candidates in the order they were found. Only tracked when the `checkpoints` option
+    /// is enabled.
+    candidate_log: Vec<String>,
+
+    /// More files were found than allowed by the `max_files` option
+    file_limit_exceeded: bool,
+
+    /// Everything that happened during the last scan. Only tracked when the `verbose` option is
+    /// enabled.
+    scan_log: Vec<ScanEvent>,
+
+    /// Custom reader for the content of files, the file system is used directly otherwise
+    content_reader: Option<sync::Arc<dyn ContentReader>>,
 }
 
 impl Scanner {
     pub fn new(sources: Option<Vec<GlobEntry>>) -> Self {
+        Self::with_options(ScanOptions {
+            sources: sources.unwrap_or_default(),
+            ..Default::default()
+        })
+    }
+
+    /// Same as [Scanner::new], but invalid sources are reported instead of being ignored.
+    pub fn try_new(sources: Option<Vec<GlobEntry>>) -> Result<Self, ScannerError> {
+        Self::try_with_options(ScanOptions {
+            sources: sources.unwrap_or_default(),
+            ..Default::default()
+        })
+    }
+
+    /// Same as [Scanner::with_options], but invalid sources and a blocklist file that can't be
+    /// read are reported instead of being ignored.
+    pub fn try_with_options(options: ScanOptions) -> Result<Self, ScannerError> {
+        for source in &options.sources {
+            let expression: Result<Expression, _> = source.pattern[..].try_into();
+            if expression.is_err() {
+                return Err(ScannerError::InvalidPattern(source.pattern.clone()));
+            }
+
+            if !resolve_base(options.base.as_deref(), &source.base).exists() {
+                return Err(ScannerError::MissingBase(source.base.clone()));
+            }
+        }
+
+        if let Some(path) = &options.blocklist_file {
+            read_blocklist(path).map_err(ScannerError::Blocklist)?;
+        }
+
+        Ok(Self::with_options(options))
+    }
+
+    pub fn with_options(options: ScanOptions) -> Self {
+        let blocklist = match &options.blocklist_file {
+            Some(path) => read_blocklist(path).unwrap_or_else(|e| {
+                event!(tracing::Level::ERROR, "Failed to read blocklist: {:?}", e);
+                Default::default()
+            }),
+            None => Default::default(),
+        };
+
+        let generated_outputs = options
+            .generated_outputs
+            .iter()
+            .map(|path| canonicalize_lossy(path))
+            .collect();
+
+        let walk_options = WalkOptions {
+            project_root: options
+                .project_root
+                .as_ref()
+                .map(|path| canonicalize_lossy(path)),
+            git_root: options
+                .git_root
+                .as_ref()
+                .map(|path| canonicalize_lossy(path)),
+            npmignore: options.npmignore,
+            path_filter: options.path_filter.clone(),
+            hidden_dirs: options.hidden_sources.as_ref().map(|dirs| {
+                dirs.iter()
+                    .flat_map(|path| [path.clone(), canonicalize_lossy(path)])
+                    .collect()
+            }),
+            only_extensions: options.only_extensions.clone(),
+        };
+
         Self {
-            sources,
+            pre_processors: PreProcessors {
+                json_key_paths: options.json_key_paths.as_ref().map(JsonKeyPaths::new),
+                ..Default::default()
+            },
+            options,
+            blocklist,
+            generated_outputs,
+            walk_options,
             ..Default::default()
         }
     }
 
+    /// Resolve relative source bases against a different directory, e.g.: when switching to
+    /// another project in a multi-root workspace.
+    ///
+    /// The options and the pre-processors are kept, but all files and candidates are resolved
+    /// again from scratch on the next scan.
+    pub fn set_base(&mut self, base: PathBuf) {
+        self.options.base = Some(base.to_string_lossy().into());
+
+        *self = Self {
+            options: std::mem::take(&mut self.options),
+            pre_processors: std::mem::take(&mut self.pre_processors),
+            blocklist: std::mem::take(&mut self.blocklist),
+            generated_outputs: std::mem::take(&mut self.generated_outputs),
+            walk_options: std::mem::take(&mut self.walk_options),
+            content_reader: self.content_reader.take(),
+            ..Default::default()
+        };
+    }
+
+    /// Use a custom reader for the content of files, e.g.: to strip the source maps of bundles
+    /// before they are scanned.
+    pub fn set_content_reader(&mut self, content_reader: impl ContentReader + 'static) {
+        self.content_reader = Some(sync::Arc::new(content_reader));
+    }
+
+    fn content_reader(&self) -> &dyn ContentReader {
+        match &self.content_reader {
+            Some(content_reader) => content_reader.as_ref(),
+            None => &FsContentReader,
+        }
+    }
+
+    /// Whether the sources resolved to more files than allowed by the `max_files` option. In that
+    /// case no files are scanned at all.
+    pub fn file_limit_exceeded(&mut self) -> bool {
+        self.prepare();
+        self.file_limit_exceeded
+    }
+
+    /// The globs that are generated for a single source, before they are merged with the globs
+    /// of all other sources. This is useful to debug why a directory is or isn't watched.
+    pub fn globs_for_source(&self, source: &GlobEntry) -> Vec<GlobEntry> {
+        self.resolve_sources(std::slice::from_ref(source)).globs
+    }
+
+    /// The extension to pre-processor mapping used when reading files.
+    pub fn pre_processors(&self) -> &FxHashMap<String, PreProcessorKind> {
+        &self.pre_processors.mapping
+    }
+
+    /// Modify the extension to pre-processor mapping, e.g.: to handle `.jinja` files with an
+    /// existing pre-processor. This should happen before scanning.
+    pub fn pre_processors_mut(&mut self) -> &mut FxHashMap<String, PreProcessorKind> {
+        &mut self.pre_processors.mapping
+    }
+
     pub fn scan(&mut self) -> Vec<String> {
         init_tracing();
 
+        self.scan_log.clear();
+
         self.prepare();
         self.compute_candidates();
 
@@ -113,10 +689,35 @@ impl Scanner {
         candidates
     }
 
+    /// Same as [Scanner::scan], but the work happens on tokio's blocking thread pool so that it
+    /// doesn't block the async runtime.
+    ///
+    /// The scan runs on a copy of the scanner, which replaces this one once the scan completes. If
+    /// the future is dropped before that, the scanner is left untouched.
+    #[cfg(feature = "async")]
+    pub async fn scan_async(&mut self) -> Result<Vec<String>, ScannerError> {
+        let mut scanner = self.clone();
+
+        let result = tokio::task::spawn_blocking(move || {
+            let candidates = scanner.scan();
+            (scanner, candidates)
+        })
+        .await;
+
+        match result {
+            Ok((scanner, candidates)) => {
+                *self = scanner;
+                Ok(candidates)
+            }
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(_) => Err(ScannerError::Cancelled),
+        }
+    }
+
     #[tracing::instrument(skip_all)]
     pub fn scan_content(&mut self, changed_content: Vec<ChangedContent>) -> Vec<String> {
         self.prepare();
-        let candidates = parse_all_blobs(read_all_files(changed_content));
+        let candidates = self.extract_candidates(changed_content);
 
         let mut new_candidates = vec![];
         for candidate in candidates {
@@ -127,9 +728,250 @@ impl Scanner {
             new_candidates.push(candidate);
         }
 
+        if self.options.checkpoints {
+            self.candidate_log.extend(new_candidates.iter().cloned());
+        }
+
+        new_candidates
+    }
+
+    /// Same as [Scanner::scan_content], but all candidates are returned together with the amount
+    /// of times they occur in the content, sorted by candidate. E.g.: for bundle size analysis.
+    #[tracing::instrument(skip_all)]
+    pub fn scan_content_with_counts(
+        &mut self,
+        changed_content: Vec<ChangedContent>,
+    ) -> Vec<(String, usize)> {
+        self.prepare();
+
+        let options = &self.options;
+        let blocklist = &self.blocklist;
+        let count_blobs = |blobs: Vec<Vec<u8>>| {
+            let mut counts: FxHashMap<String, usize> = FxHashMap::default();
+
+            for (candidate, count) in count_all_blobs(blobs) {
+                let candidate = match options.canonicalize_arbitrary_values {
+                    true => canonicalize_arbitrary_values(&candidate).into_owned(),
+                    false => candidate,
+                };
+
+                let candidate = match options.lowercase_candidates {
+                    true => candidate.to_lowercase(),
+                    false => candidate,
+                };
+
+                let is_allowed = !blocklist.contains(&candidate)
+                    && !options
+                        .noise_filter
+                        .is_some_and(|noise_filter| noise_filter.is_noise(&candidate));
+
+                if is_allowed {
+                    *counts.entry(candidate).or_default() += count;
+                }
+            }
+
+            counts
+        };
+
+        let per_source: Vec<_> = if self.options.provenance {
+            // Every file has to be counted on its own to know where the candidates came from.
+            read_all_files_by_source(
+                changed_content,
+                &self.pre_processors,
+                self.content_reader(),
+                self.options.on_progress.as_ref(),
+            )
+            .into_par_iter()
+            .map(|(source, blob)| (source, count_blobs(blob.into_iter().collect())))
+            .collect()
+        } else {
+            vec![(
+                None,
+                count_blobs(read_all_files(
+                    changed_content,
+                    &self.pre_processors,
+                    self.content_reader(),
+                    self.options.on_progress.as_ref(),
+                )),
+            )]
+        };
+
+        let mut counts: FxHashMap<String, usize> = FxHashMap::default();
+
+        for (source, source_counts) in per_source {
+            if let Some(source) = source {
+                self.track_source(source, source_counts.keys().cloned().collect());
+            }
+
+            for (candidate, count) in source_counts {
+                *counts.entry(candidate).or_default() += count;
+            }
+        }
+
+        for candidate in counts.keys() {
+            if self.candidates.insert(candidate.clone()) && self.options.checkpoints {
+                self.candidate_log.push(candidate.clone());
+            }
+        }
+
+        let mut counts: Vec<_> = counts.into_iter().collect();
+        counts.par_sort_unstable();
+        counts
+    }
+
+    /// Same as [Scanner::scan_content], but for multiple batches of content at once, e.g.: from
+    /// different sources. Returns all candidates that are new across all batches, sorted.
+    #[tracing::instrument(skip_all)]
+    pub fn scan_content_batched(&mut self, batches: Vec<Vec<ChangedContent>>) -> Vec<String> {
+        let mut new_candidates: Vec<String> = batches
+            .into_iter()
+            .flat_map(|batch| self.scan_content(batch))
+            .collect();
+
+        new_candidates.par_sort_unstable();
         new_candidates
     }
 
+    /// Same as [Scanner::scan], but only the candidates that are not part of the `baseline` are
+    /// returned, e.g.: the candidates that were persisted before a restart.
+    pub fn scan_against_baseline(&mut self, baseline: &FxHashSet<String>) -> Vec<String> {
+        let mut candidates = self.scan();
+        candidates.retain(|candidate| !baseline.contains(candidate));
+        candidates
+    }
+
+    /// Same as [Scanner::scan], but the files are scanned directory by directory, e.g.: for the
+    /// first scan of a huge monorepo that can be interrupted.
+    ///
+    /// After every directory, `checkpoint` receives a token with everything that was scanned so
+    /// far. Persist it, and pass it to a new scanner to skip the completed directories when the
+    /// scan is restarted. Returning [ControlFlow::Break] stops the scan, in which case `None` is
+    /// returned.
+    pub fn scan_resumable(
+        &mut self,
+        token: Option<ResumeToken>,
+        mut checkpoint: impl FnMut(&ResumeToken) -> ControlFlow<()>,
+    ) -> Option<Vec<String>> {
+        init_tracing();
+
+        self.scan_log.clear();
+        self.prepare();
+
+        let mut token = token.unwrap_or_default();
+        self.candidates.extend(token.candidates.iter().cloned());
+
+        // Directories are scanned in a stable order, so that a restart continues where the
+        // previous scan stopped.
+        let mut files_by_dir: FxHashMap<PathBuf, Vec<PathBuf>> = FxHashMap::default();
+        let content_hash = self.options.content_hash;
+
+        for path in &self.files {
+            let dir = path
+                .parent()
+                .map(|dir| dir.to_path_buf())
+                .unwrap_or_default();
+
+            // Files of completed directories are not read again
+            if token.completed_dirs.contains(&dir) {
+                self.file_states
+                    .insert(path.clone(), FileState::read(path, content_hash));
+                continue;
+            }
+
+            files_by_dir.entry(dir).or_default().push(path.clone());
+        }
+
+        let mut files_by_dir: Vec<_> = files_by_dir.into_iter().collect();
+        files_by_dir.sort_unstable();
+
+        for (dir, files) in files_by_dir {
+            for path in &files {
+                self.file_states
+                    .insert(path.clone(), FileState::read(path, content_hash));
+            }
+
+            let changed_content = files.iter().map(|path| self.changed_file(path)).collect();
+
+            for candidate in self.extract_candidates(changed_content) {
+                if self.candidates.insert(candidate.clone()) && self.options.checkpoints {
+                    self.candidate_log.push(candidate.clone());
+                }
+
+                token.candidates.insert(candidate);
+            }
+
+            token.completed_dirs.insert(dir);
+
+            if checkpoint(&token).is_break() {
+                return None;
+            }
+        }
+
+        let mut candidates: Vec<String> = self.candidates.clone().into_par_iter().collect();
+        candidates.par_sort_unstable();
+
+        Some(candidates)
+    }
+
+    /// Mark the current set of candidates, so that [Scanner::candidates_since] can return the
+    /// candidates that were found afterwards.
+    ///
+    /// This requires the `checkpoints` option, otherwise no candidates are ever returned.
+    pub fn checkpoint(&mut self) -> CheckpointId {
+        CheckpointId(self.candidate_log.len())
+    }
+
+    /// All candidates that were found after the given checkpoint, sorted.
+    pub fn candidates_since(&self, checkpoint: CheckpointId) -> Vec<String> {
+        let mut candidates = self
+            .candidate_log
+            .get(checkpoint.0..)
+            .unwrap_or_default()
+            .to_vec();
+        candidates.par_sort_unstable();
+        candidates
+    }
+
+    /// Scan all files for CSS variables only, e.g.: `--my-color` in `var(--my-color)`.
+    ///
+    /// This is a dedicated pass over all known files and doesn't influence the candidates that
+    /// are tracked by the scanner.
+    #[tracing::instrument(skip_all)]
+    pub fn scan_variables(&mut self) -> Vec<String> {
+        init_tracing();
+
+        self.prepare();
+
+        let changed_content = self
+            .files
+            .iter()
+            .map(|path| self.changed_file(path))
+            .collect();
+
+        extract_all_blobs(
+            read_all_files(
+                changed_content,
+                &self.pre_processors,
+                self.content_reader(),
+                self.options.on_progress.as_ref(),
+            ),
+            |extracted| match extracted {
+                Extracted::Candidate(_) => None,
+                Extracted::CssVariable(bytes) => Some(bytes),
+            },
+        )
+    }
+
+    /// Approximate amount of bytes used by the tracked candidates, including the overhead of the
+    /// set itself. This is meant for diagnostics and is not exact.
+    pub fn candidate_memory_estimate(&self) -> usize {
+        // Every slot in the set stores a `String` and a control byte
+        let table = self.candidates.capacity() * (std::mem::size_of::<String>() + 1);
+        let strings = self.candidates.iter().map(|x| x.capacity()).sum::<usize>();
+
+        table + strings
+    }
+
     #[tracing::instrument(skip_all)]
     pub fn get_candidates_with_positions(
         &mut self,
@@ -137,38 +979,37 @@ impl Scanner {
     ) -> Vec<(String, usize)> {
         self.prepare();
 
-        let content = read_changed_content(changed_content).unwrap_or_default();
-        let original_content = &content;
+        let content =
+            read_changed_content(changed_content, &self.pre_processors, self.content_reader())
+                .unwrap_or_default();
 
-        // Workaround for legacy upgrades:
-        //
-        // `-[]` won't parse in the new parser (`[…]` must contain _something_), but we do need it
-        // for people using `group-[]` (which we will later replace with `in-[.group]` instead).
-        let content = content.replace("-[]", "XYZ");
-        let offset = content.as_ptr() as usize;
+        candidates_with_positions(&content)
+    }
 
-        let mut extractor = Extractor::new(&content[..]);
+    /// Same as [Scanner::get_candidates_with_positions], but for multiple files at once, e.g.: all
+    /// open buffers of an editor. Positions are relative to the start of each file. Content that
+    /// is not backed by a file is returned with an empty path.
+    pub fn get_candidates_with_positions_batch(
+        &mut self,
+        changed_content: Vec<ChangedContent>,
+    ) -> Vec<(PathBuf, Vec<(String, usize)>)> {
+        self.prepare();
+
+        let pre_processors = &self.pre_processors;
+        let content_reader = self.content_reader();
 
-        extractor
-            .extract()
+        changed_content
             .into_par_iter()
-            .flat_map(|extracted| match extracted {
-                Extracted::Candidate(s) => {
-                    let i = s.as_ptr() as usize - offset;
-                    let original = &original_content[i..i + s.len()];
-                    if original.contains_str("-[]") {
-                        return Some(unsafe {
-                            (String::from_utf8_unchecked(original.to_vec()), i)
-                        });
-                    }
+            .map(|changed_content| {
+                let path = match &changed_content {
+                    ChangedContent::File(path, _) => path.clone(),
+                    _ => PathBuf::new(),
+                };
 
-                    // SAFETY: When we parsed the candidates, we already guaranteed that the byte
-                    // slices are valid, therefore we don't have to re-check here when we want to
-                    // convert it back to a string.
-                    Some(unsafe { (String::from_utf8_unchecked(s.to_vec()), i) })
-                }
+                let content = read_changed_content(changed_content, pre_processors, content_reader)
+                    .unwrap_or_default();
 
-                _ => None,
+                (path, candidates_with_positions(&content))
             })
             .collect()
     }
@@ -176,65 +1017,551 @@ impl Scanner {
     #[tracing::instrument(skip_all)]
     pub fn get_files(&mut self) -> Vec<String> {
         self.prepare();
+        self.collect_files()
+    }
 
-        self.files
+    #[tracing::instrument(skip_all)]
+    pub fn get_globs(&mut self) -> Vec<GlobEntry> {
+        self.prepare();
+        self.collect_globs()
+    }
+
+    /// All scanned files matching the given glob, e.g.: `**/*.tsx`. Relative globs are matched
+    /// against the path relative to the base of any of the sources. The files are sorted.
+    #[tracing::instrument(skip_all)]
+    pub fn files_matching(&mut self, glob: &str) -> Vec<String> {
+        self.prepare();
+
+        let is_absolute = glob.starts_with('/') || glob.chars().nth(1) == Some(':');
+        let bases: Vec<String> = self
+            .options
+            .sources
+            .iter()
+            .filter_map(|source| {
+                Path::from(resolve_base(self.options.base.as_deref(), &source.base))
+                    .canonicalize()
+                    .ok()
+            })
+            .map(|base| base.to_string())
+            .collect();
+
+        let mut files: Vec<String> = self
+            .files
             .par_iter()
             .filter_map(|x| Path::from(x.clone()).canonicalize().ok())
             .map(|x| x.to_string())
+            .filter(|path| {
+                if is_absolute {
+                    return glob_match(glob, path);
+                }
+
+                bases.iter().any(|base| {
+                    path.strip_prefix(base.as_str())
+                        .and_then(|path| path.strip_prefix('/'))
+                        .is_some_and(|path| glob_match(glob, path))
+                })
+            })
+            .map(|x| self.output_path(x))
+            .collect();
+
+        files.par_sort_unstable();
+        files.dedup();
+        files
+    }
+
+    /// Scan for candidates and collect the files and globs at the same time. This is the same as
+    /// calling `scan`, `get_files` and `get_globs`, but only checks the file system once.
+    #[tracing::instrument(skip_all)]
+    pub fn scan_all(&mut self) -> ScanResult {
+        let candidates = self.scan();
+
+        ScanResult {
+            candidates,
+            files: self.collect_files(),
+            globs: self.collect_globs(),
+        }
+    }
+
+    fn collect_files(&self) -> Vec<String> {
+        self.files
+            .par_iter()
+            .filter_map(|x| Path::from(x.clone()).canonicalize().ok())
+            .map(|x| self.output_path(x.to_string()))
             .collect()
     }
 
+    fn collect_globs(&self) -> Vec<GlobEntry> {
+        self.globs
+            .iter()
+            .map(|glob| GlobEntry {
+                base: self.output_path(glob.base.clone()),
+                pattern: glob.pattern.clone(),
+            })
+            .collect()
+    }
+
+    /// All directories that are watched for new files.
     #[tracing::instrument(skip_all)]
-    pub fn get_globs(&mut self) -> Vec<GlobEntry> {
+    pub fn get_dirs(&mut self) -> Vec<String> {
         self.prepare();
 
-        self.globs.clone()
+        self.dirs
+            .par_iter()
+            .filter_map(|x| Path::from(x.clone()).canonicalize().ok())
+            .map(|x| self.output_path(x.to_string()))
+            .collect()
+    }
+
+    // Apply the output related options to a path that is exposed to the outside world.
+    fn output_path(&self, path: String) -> String {
+        let path = match &self.options.path_remap {
+            Some(path_remap) => path_remap.apply(&path),
+            None => path,
+        };
+
+        if self.options.normalize_separators {
+            path.replace('\\', "/")
+        } else {
+            path
+        }
     }
 
     #[tracing::instrument(skip_all)]
     fn compute_candidates(&mut self) {
         let mut changed_content = vec![];
 
-        let current_mtimes = self
+        let content_hash = self.options.content_hash;
+        let current_states = self
             .files
             .par_iter()
-            .map(|path| {
-                fs::metadata(path)
-                    .and_then(|m| m.modified())
-                    .unwrap_or(SystemTime::now())
-            })
+            .map(|path| FileState::read(path, content_hash))
             .collect::<Vec<_>>();
 
-        for (idx, path) in self.files.iter().enumerate() {
-            let current_time = current_mtimes[idx];
-            let previous_time = self.mtimes.insert(path.clone(), current_time);
+        for (idx, path) in self.files.iter().enumerate() {
+            let current_state = current_states[idx];
+            let previous_state = self.file_states.insert(path.clone(), current_state);
+
+            let should_scan_file = match previous_state {
+                // File has changed, so we need to re-scan the file
+                Some(prev) if prev.has_changed(&current_state) => true,
+
+                // File was in the cache, no need to re-scan
+                Some(_) => false,
+
+                // File didn't exist before, so we need to scan it
+                None => true,
+            };
+
+            if should_scan_file {
+                changed_content.push(self.changed_file(path));
+            }
+        }
+
+        self.log(ScanEvent::FilesRead(changed_content.len()));
+
+        let known_candidates = self.candidates.len();
+
+        if !changed_content.is_empty() {
+            let candidates = self.extract_candidates(changed_content);
+
+            if self.options.checkpoints {
+                for candidate in candidates {
+                    if self.candidates.insert(candidate.clone()) {
+                        self.candidate_log.push(candidate);
+                    }
+                }
+            } else {
+                self.candidates.par_extend(candidates);
+            }
+        }
+
+        self.log(ScanEvent::NewCandidates(
+            self.candidates.len() - known_candidates,
+        ));
+    }
+
+    // The content to read for a file, the extension decides which pre-processor is used.
+    fn changed_file(&self, path: &std::path::Path) -> ChangedContent<'static> {
+        let extension = match (content_extension(path), &self.options.default_extension) {
+            (Some(extension), _) => extension.into_owned(),
+            (None, Some(default_extension)) => default_extension.clone(),
+            (None, None) => String::new(),
+        };
+
+        ChangedContent::File(path.to_path_buf(), extension.into())
+    }
+
+    /// Apply a batch of file events, e.g.: from a file watcher. Created and modified files are
+    /// read again, and the candidates of deleted files are dropped. Files that are not part of
+    /// the sources are skipped, e.g.: ignored files.
+    ///
+    /// Candidates can only be removed with the `provenance` option, because that's what keeps
+    /// track of the files that contribute each candidate.
+    #[tracing::instrument(skip_all)]
+    pub fn apply_changes(
+        &mut self,
+        created: Vec<PathBuf>,
+        modified: Vec<PathBuf>,
+        deleted: Vec<PathBuf>,
+    ) -> ScanDelta {
+        self.prepare();
+
+        let deleted: FxHashSet<PathBuf> = deleted.into_iter().collect();
+        self.files.retain(|path| !deleted.contains(path));
+
+        let mut known = FxHashSet::from_iter(self.files.iter().cloned());
+        let mut changed = vec![];
+
+        let (known_paths, unknown_paths): (Vec<_>, Vec<_>) = created
+            .into_iter()
+            .chain(modified)
+            .partition(|path| known.contains(path));
+        let paths = known_paths
+            .into_iter()
+            .chain(self.filter_unknown_files(unknown_paths));
+
+        for path in paths {
+            if deleted.contains(&path) || self.is_generated_output(&path) {
+                continue;
+            }
+
+            if known.insert(path.clone()) {
+                self.files.push(path.clone());
+            }
+
+            changed.push(path);
+        }
+
+        // Everything the changed and deleted files contributed so far. These candidates are
+        // removed when no file contributes them anymore.
+        let previous: Vec<String> = changed
+            .iter()
+            .chain(deleted.iter())
+            .filter_map(|path| self.candidates_by_source.get(path))
+            .flatten()
+            .cloned()
+            .collect();
+
+        for path in &deleted {
+            self.file_states.remove(path);
+            self.track_source(path.clone(), vec![]);
+        }
+
+        let content_hash = self.options.content_hash;
+        for path in &changed {
+            self.file_states
+                .insert(path.clone(), FileState::read(path, content_hash));
+        }
+
+        let changed_content = changed.iter().map(|path| self.changed_file(path)).collect();
+        let candidates = self.extract_candidates(changed_content);
+
+        let mut delta = ScanDelta::default();
+
+        for candidate in candidates {
+            if self.candidates.insert(candidate.clone()) {
+                if self.options.checkpoints {
+                    self.candidate_log.push(candidate.clone());
+                }
+
+                delta.added.push(candidate);
+            }
+        }
+
+        for candidate in previous {
+            if !self.sources_by_candidate.contains_key(&candidate)
+                && self.candidates.remove(&candidate)
+            {
+                delta.removed.push(candidate);
+            }
+        }
+
+        delta.added.par_sort_unstable();
+        delta.removed.par_sort_unstable();
+        delta
+    }
+
+    /// Everything that happened during the last scan, e.g.: to attach to a bug report.
+    ///
+    /// This requires the `verbose` option, otherwise the log is always empty.
+    pub fn last_scan_log(&self) -> &[ScanEvent] {
+        &self.scan_log
+    }
+
+    fn log(&mut self, event: ScanEvent) {
+        if self.options.verbose {
+            self.scan_log.push(event);
+        }
+    }
+
+    /// Whether any of the known files or directories changed since the last scan. This only
+    /// looks at modification times and file sizes, which is a lot cheaper than a full `scan`.
+    pub fn has_changes(&self) -> bool {
+        let Some(last_checked) = self.last_checked else {
+            return true;
+        };
+
+        let dirs_changed = self.dirs.par_iter().any(|path| {
+            let current = fs::metadata(path).and_then(|m| m.modified()).ok();
+
+            match (self.mtimes.get(path), current) {
+                (Some(previous), Some(current)) => *previous != current,
+
+                // Directory was never checked for new files, compare with the last check instead
+                (None, Some(current)) => current >= last_checked,
+
+                // Directory doesn't exist anymore
+                (_, None) => true,
+            }
+        });
+
+        dirs_changed
+            || self
+                .files
+                .par_iter()
+                .any(|path| match self.file_states.get(path) {
+                    Some(previous) => previous.has_changed(&FileState::read(path, false)),
+
+                    // File was never scanned
+                    None => true,
+                })
+    }
+
+    /// Forget the state of the given file, so that the next scan reads it again even if its
+    /// modification time didn't change, e.g.: on network file systems with unreliable mtimes.
+    pub fn clear_file_cache(&mut self, path: &std::path::Path) {
+        self.file_states.remove(path);
+        self.file_states.remove(&canonicalize_lossy(path));
+    }
+
+    /// Same as [Scanner::clear_file_cache], but for all files.
+    pub fn clear_all_file_caches(&mut self) {
+        self.file_states.clear();
+    }
+
+    /// All files that currently contribute the given candidate, sorted by path.
+    ///
+    /// This requires the `provenance` option, otherwise no files are returned.
+    pub fn source_for_candidate(&self, candidate: &str) -> Vec<PathBuf> {
+        let Some(sources) = self.sources_by_candidate.get(candidate) else {
+            return vec![];
+        };
+
+        let mut sources: Vec<PathBuf> = sources.iter().cloned().collect();
+        sources.sort_unstable();
+        sources
+    }
+
+    /// How many distinct files contribute each candidate, sorted by candidate. Candidates that
+    /// only occur in a single file are good targets for a cleanup.
+    ///
+    /// This requires the `provenance` option, otherwise `None` is returned.
+    pub fn candidate_frequency(&self) -> Option<Vec<(String, usize)>> {
+        if !self.options.provenance {
+            return None;
+        }
+
+        let mut frequency: Vec<(String, usize)> = self
+            .sources_by_candidate
+            .iter()
+            .map(|(candidate, sources)| (candidate.clone(), sources.len()))
+            .collect();
+
+        frequency.sort_unstable();
+        Some(frequency)
+    }
+
+    /// Scan all files and group the candidates by the extension of the files they were found
+    /// in, e.g.: `{"html": ["flex"], "tsx": ["flex", "underline"]}`. Candidates are sorted and
+    /// can be part of multiple groups.
+    ///
+    /// This requires the `provenance` option, otherwise nothing is scanned and `None` is returned.
+    pub fn scan_by_extension(&mut self) -> Option<FxHashMap<String, Vec<String>>> {
+        if !self.options.provenance {
+            return None;
+        }
+
+        self.scan();
+
+        let mut result: FxHashMap<String, FxHashSet<&String>> = FxHashMap::default();
+
+        for (source, candidates) in &self.candidates_by_source {
+            let extension = content_extension(source).unwrap_or_default();
+
+            result
+                .entry(extension.into_owned())
+                .or_default()
+                .extend(candidates);
+        }
+
+        let result = result
+            .into_iter()
+            .map(|(extension, candidates)| {
+                let mut candidates: Vec<String> = candidates.into_iter().cloned().collect();
+                candidates.par_sort_unstable();
+                (extension, candidates)
+            })
+            .collect();
+
+        Some(result)
+    }
+
+    fn extract_candidates(&mut self, changed_content: Vec<ChangedContent>) -> Vec<String> {
+        let blocklist = &self.blocklist;
+        let noise_filter = self.options.noise_filter;
+        let is_allowed = |candidate: &String| {
+            !blocklist.contains(candidate)
+                && !noise_filter.is_some_and(|noise_filter| noise_filter.is_noise(candidate))
+        };
+
+        let canonicalize = self.options.canonicalize_arbitrary_values;
+        let lowercase = self.options.lowercase_candidates;
+        let finalize = |candidates: Vec<String>| {
+            let mut candidates = if canonicalize || lowercase {
+                let mut candidates: Vec<String> = candidates
+                    .iter()
+                    .map(|candidate| match canonicalize {
+                        true => canonicalize_arbitrary_values(candidate),
+                        false => Cow::Borrowed(candidate.as_str()),
+                    })
+                    .map(|candidate| match lowercase {
+                        true => candidate.to_lowercase(),
+                        false => candidate.into_owned(),
+                    })
+                    .collect();
+                candidates.sort_unstable();
+                candidates.dedup();
+                candidates
+            } else {
+                candidates
+            };
+
+            candidates.retain(is_allowed);
+            candidates
+        };
+
+        if !self.options.provenance {
+            return finalize(parse_all_blobs(read_all_files(
+                changed_content,
+                &self.pre_processors,
+                self.content_reader(),
+                self.options.on_progress.as_ref(),
+            )));
+        }
+
+        // Every file has to be parsed on its own to know where the candidates came from.
+        let per_source: Vec<_> = read_all_files_by_source(
+            changed_content,
+            &self.pre_processors,
+            self.content_reader(),
+            self.options.on_progress.as_ref(),
+        )
+        .into_par_iter()
+        .map(|(source, blob)| {
+            let candidates = blob
+                .map(|blob| parse_all_blobs(vec![blob]))
+                .unwrap_or_default();
+            (source, finalize(candidates))
+        })
+        .collect();
+
+        let mut result = FxHashSet::default();
+
+        for (source, candidates) in per_source {
+            result.extend(candidates.iter().cloned());
+
+            if let Some(source) = source {
+                self.track_source(source, candidates);
+            }
+        }
+
+        let mut result: Vec<String> = result.into_iter().collect();
+        result.par_sort_unstable();
+        result
+    }
+
+    /// Remove a source, e.g.: when a package is removed from a workspace. Files that are no
+    /// longer part of any of the remaining sources are forgotten.
+    ///
+    /// With the `provenance` option, candidates that were only found in those files are removed
+    /// as well. Otherwise all candidates are kept.
+    pub fn remove_source(&mut self, source: &GlobEntry) {
+        self.options.sources.retain(|x| x != source);
+
+        // Nothing was resolved yet, the remaining sources are resolved on the next scan
+        if !self.ready {
+            return;
+        }
+
+        let previous_files = std::mem::take(&mut self.files);
+        self.dirs.clear();
+        self.globs.clear();
+        self.explicit_patterns.clear();
+        self.file_limit_exceeded = false;
 
-            let should_scan_file = match previous_time {
-                // Time has changed, so we need to re-scan the file
-                Some(prev) if prev != current_time => true,
+        self.scan_sources();
 
-                // File was in the cache, no need to re-scan
-                Some(_) => false,
+        let dirs = FxHashSet::from_iter(self.dirs.iter());
+        self.mtimes.retain(|dir, _| dirs.contains(dir));
 
-                // File didn't exist before, so we need to scan it
-                None => true,
+        let files = FxHashSet::from_iter(self.files.iter());
+        let removed_files: Vec<_> = previous_files
+            .into_iter()
+            .filter(|path| !files.contains(path))
+            .collect();
+
+        for path in removed_files {
+            self.file_states.remove(&path);
+
+            let Some(candidates) = self.candidates_by_source.get(&path).cloned() else {
+                continue;
             };
 
-            if should_scan_file {
-                let extension = path.extension().unwrap_or_default().to_string_lossy();
-                changed_content.push(ChangedContent::File(path.to_path_buf(), extension))
+            self.track_source(path, vec![]);
+
+            for candidate in candidates {
+                if !self.sources_by_candidate.contains_key(&candidate) {
+                    self.candidates.remove(&candidate);
+                }
             }
         }
+    }
 
-        if !changed_content.is_empty() {
-            let candidates = parse_all_blobs(read_all_files(changed_content));
-            self.candidates.par_extend(candidates);
+    // Replace the candidates that were previously found in `source`.
+    fn track_source(&mut self, source: PathBuf, candidates: Vec<String>) {
+        if let Some(previous) = self.candidates_by_source.remove(&source) {
+            for candidate in previous {
+                let Some(sources) = self.sources_by_candidate.get_mut(&candidate) else {
+                    continue;
+                };
+
+                sources.remove(&source);
+
+                if sources.is_empty() {
+                    self.sources_by_candidate.remove(&candidate);
+                }
+            }
+        }
+
+        if candidates.is_empty() {
+            return;
+        }
+
+        for candidate in &candidates {
+            self.sources_by_candidate
+                .entry(candidate.clone())
+                .or_default()
+                .insert(source.clone());
         }
+
+        self.candidates_by_source.insert(source, candidates);
     }
 
     // Ensures that all files/globs are resolved and the scanner is ready to scan
     // content for candidates.
     fn prepare(&mut self) {
+        self.last_checked = Some(SystemTime::now());
+
         if self.ready {
             self.check_for_new_files();
             return;
@@ -280,13 +1607,17 @@ impl Scanner {
             }
         }
 
+        let known_entries = self.files.len() + self.dirs.len();
+
         // Scan all modified directories for their immediate files
         let mut known = FxHashSet::from_iter(self.files.iter().chain(self.dirs.iter()).cloned());
 
         while !modified_dirs.is_empty() {
             let new_entries = modified_dirs
                 .iter()
-                .flat_map(|dir| read_dir(dir, Some(1)))
+                .flat_map(|dir| {
+                    read_dir_with(dir, &self.walk_options, Some(1), self.is_allowed_file())
+                })
                 .map(|entry| entry.path().to_owned())
                 .filter(|path| !known.contains(path))
                 .collect::<Vec<_>>();
@@ -295,8 +1626,30 @@ impl Scanner {
 
             for path in new_entries {
                 if path.is_file() {
+                    if has_any_extension(&path, &self.options.excluded_extensions)
+                        || self.is_generated_output(&path)
+                    {
+                        continue;
+                    }
+
                     known.insert(path.clone());
                     self.files.push(path);
+
+                    // Same as for the initial scan, nothing is scanned at all once there are too
+                    // many files.
+                    if self
+                        .options
+                        .max_files
+                        .is_some_and(|max_files| self.files.len() > max_files)
+                    {
+                        self.files.clear();
+                        self.dirs.clear();
+                        self.globs.clear();
+                        self.explicit_patterns.clear();
+                        self.mtimes.clear();
+                        self.report_file_limit_exceeded();
+                        return;
+                    }
                 } else if path.is_dir() {
                     known.insert(path.clone());
                     self.dirs.push(path.clone());
@@ -306,32 +1659,146 @@ impl Scanner {
                 }
             }
         }
+
+        if self.options.deterministic {
+            self.sort_sources();
+        }
+
+        self.log(ScanEvent::NewFiles(
+            self.files.len() + self.dirs.len() - known_entries,
+        ));
     }
 
-    #[tracing::instrument(skip_all)]
-    fn scan_sources(&mut self) {
-        let Some(sources) = &self.sources else {
-            return;
-        };
+    // Whether a file that is found while walking a directory should be scanned. Either because it
+    // matches an explicit source, or based on the rules of auto source detection.
+    fn is_allowed_file(&self) -> impl Fn(&std::path::Path) -> bool + Send + Sync + 'static {
+        let explicit_patterns = self.explicit_patterns.clone();
+        let is_detectable_file = self.is_detectable_file();
 
-        if sources.is_empty() {
-            return;
+        move |path| matches_any_pattern(&explicit_patterns, path) || is_detectable_file(path)
+    }
+
+    // Whether a file is picked up by auto source detection. Ignore files are handled by the walk
+    // itself.
+    fn is_detectable_file(&self) -> impl Fn(&std::path::Path) -> bool + Send + Sync + 'static {
+        let content_signatures = self.options.content_signatures.clone();
+        let default_ignores = !self.options.no_default_ignores;
+        let declaration_files = self.options.include_declaration_files;
+        let walk_options = self.walk_options.clone();
+
+        move |path| {
+            walk_options.allows_extension(path)
+                && (!default_ignores
+                    || is_allowed_content_path(path)
+                    || (declaration_files && is_declaration_file(path))
+                    || matches_content_signature(path, &content_signatures))
         }
+    }
+
+    // Only keep the files that would have been picked up by the sources, e.g.: a file watcher also
+    // reports changes to ignored files.
+    fn filter_unknown_files(&self, paths: Vec<PathBuf>) -> Vec<PathBuf> {
+        let known_dirs = FxHashSet::from_iter(self.dirs.iter());
+
+        // Directories are canonicalized, so they can be compared with the watched directories
+        let mut by_dir: FxHashMap<PathBuf, Vec<PathBuf>> = FxHashMap::default();
+        for path in paths {
+            if let Some(dir) = path.parent() {
+                by_dir
+                    .entry(canonicalize_lossy(dir))
+                    .or_default()
+                    .push(path);
+            }
+        }
+
+        by_dir
+            .into_iter()
+            .flat_map(|(dir, paths)| {
+                // Only files in watched directories and files matching an explicit source can be
+                // new, everything else is outside of the sources.
+                let paths: Vec<_> = paths
+                    .into_iter()
+                    .filter_map(|path| {
+                        let canonical = dir.join(path.file_name()?);
+                        (known_dirs.contains(&dir)
+                            || matches_any_pattern(&self.explicit_patterns, &canonical))
+                        .then_some((canonical, path))
+                    })
+                    .collect();
+
+                if paths.is_empty() {
+                    return vec![];
+                }
+
+                let allowed: FxHashSet<PathBuf> =
+                    read_dir_with(&dir, &self.walk_options, Some(1), self.is_allowed_file())
+                        .map(|entry| entry.into_path())
+                        .collect();
+
+                paths
+                    .into_iter()
+                    .filter(|(canonical, _)| allowed.contains(canonical))
+                    .map(|(_, path)| path)
+                    .collect()
+            })
+            .filter(|path| !has_any_extension(path, &self.options.excluded_extensions))
+            .collect()
+    }
+
+    // Whether the file is one of the `generated_outputs` or looks like generated CSS, which should
+    // never be scanned.
+    fn is_generated_output(&self, path: &std::path::Path) -> bool {
+        let is_generated_output = !self.generated_outputs.is_empty()
+            && (self.generated_outputs.contains(path)
+                || self.generated_outputs.contains(&canonicalize_lossy(path)));
+
+        is_generated_output || (!self.options.scan_generated_css && is_generated_css(path))
+    }
+
+    // Sort the files, directories and globs, so they no longer depend on the order in which the
+    // file system returned them.
+    fn sort_sources(&mut self) {
+        self.files.par_sort_unstable();
+        self.dirs.par_sort_unstable();
+        self.globs
+            .sort_unstable_by(|a, z| (&a.base, &a.pattern).cmp(&(&z.base, &z.pattern)));
+    }
+
+    // Resolve the files, globs and directories for the given sources. The globs are not optimized
+    // yet.
+    #[tracing::instrument(skip_all)]
+    fn resolve_sources(&self, sources: &[GlobEntry]) -> ResolvedSources {
+        let mut files = vec![];
+        let mut globs = vec![];
+        let mut dirs = vec![];
+        let mut patterns = vec![];
+        let mut roots = vec![];
+
+        let max_files = self.options.max_files;
+        let limit_exceeded =
+            |files: &Vec<PathBuf>| max_files.is_some_and(|max_files| files.len() > max_files);
 
         // Expand glob patterns and create new `GlobEntry` instances for each expanded pattern.
         let sources = sources
             .iter()
             .flat_map(|source| {
+                let base = resolve_base(self.options.base.as_deref(), &source.base)
+                    .to_string_lossy()
+                    .to_string();
+
                 let expression: Result<Expression, _> = source.pattern[..].try_into();
                 let Ok(expression) = expression else {
-                    return vec![source.clone()];
+                    return vec![GlobEntry {
+                        base,
+                        pattern: source.pattern.clone(),
+                    }];
                 };
 
                 expression
                     .into_iter()
                     .filter_map(Result::ok)
                     .map(move |pattern| GlobEntry {
-                        base: source.base.clone(),
+                        base: base.clone(),
                         pattern: pattern.into(),
                     })
                     .collect::<Vec<_>>()
@@ -379,18 +1846,43 @@ impl Scanner {
             dunce::canonicalize(join_paths(&source.base, &source.pattern)).ok()
         }) {
             // Insert a glob for the base path, so we can see new files/folders in the directory itself.
-            self.globs.push(GlobEntry {
+            // Without auto detected globs, the source itself is the only glob.
+            globs.push(GlobEntry {
                 base: path.to_string_lossy().into(),
-                pattern: "*".into(),
+                pattern: if self.options.no_auto_detect_globs {
+                    "**/*".into()
+                } else {
+                    "*".into()
+                },
             });
 
+            roots.push(path.clone());
+
             // Detect all files/folders in the directory
-            let detect_sources = DetectSources::new(path);
+            let detect_sources = DetectSources::new(
+                path,
+                DetectOptions {
+                    excluded_extensions: self.options.excluded_extensions.clone(),
+                    max_files: max_files.map(|max_files| max_files.saturating_sub(files.len())),
+                    is_allowed: PathFilter::new(self.is_detectable_file()),
+                    walk_options: self.walk_options.clone(),
+                },
+            );
+
+            let (detected_files, detected_globs, detected_dirs) = detect_sources.detect();
+            files.extend(detected_files);
+            dirs.extend(detected_dirs);
 
-            let (files, globs, dirs) = detect_sources.detect();
-            self.files.extend(files);
-            self.globs.extend(globs);
-            self.dirs.extend(dirs);
+            if !self.options.no_auto_detect_globs {
+                globs.extend(detected_globs);
+            }
+
+            if limit_exceeded(&files) {
+                return ResolvedSources {
+                    limit_exceeded: true,
+                    ..Default::default()
+                };
+            }
         }
 
         // Turn `Vec<&GlobEntry>` in `Vec<GlobEntry>`
@@ -417,13 +1909,11 @@ impl Scanner {
                 full_pattern.push_str(&source.pattern);
             }
 
-            let base = PathBuf::from(&source.base);
-            for entry in resolve_paths(&base) {
-                let Some(file_type) = entry.file_type() else {
-                    continue;
-                };
+            patterns.push(full_pattern.clone());
 
-                if !file_type.is_file() {
+            let base = PathBuf::from(&source.base);
+            for entry in resolve_paths(&base, &self.walk_options) {
+                if !is_file_or_symlinked_file(&entry) {
                     continue;
                 }
 
@@ -436,22 +1926,277 @@ impl Scanner {
                 let file_path_str = file_path_str.replace('\\', "/");
 
                 if glob_match(&full_pattern, &file_path_str) {
-                    self.files.push(file_path);
+                    files.push(file_path);
+
+                    if limit_exceeded(&files) {
+                        return ResolvedSources {
+                            limit_exceeded: true,
+                            ..Default::default()
+                        };
+                    }
                 }
             }
         }
 
-        self.globs.extend(hoisted);
+        globs.extend(hoisted);
+
+        ResolvedSources {
+            files,
+            globs,
+            dirs,
+            patterns,
+            roots,
+            limit_exceeded: false,
+        }
+    }
+
+    fn report_file_limit_exceeded(&mut self) {
+        event!(
+            tracing::Level::ERROR,
+            "More than {:?} files found, is a source pointing at the wrong directory?",
+            self.options.max_files
+        );
+        self.file_limit_exceeded = true;
+        self.log(ScanEvent::FileLimitExceeded(
+            self.options.max_files.unwrap_or_default(),
+        ));
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn scan_sources(&mut self) {
+        if self.options.sources.is_empty() {
+            return;
+        }
+
+        let resolved = self.resolve_sources(&self.options.sources);
+
+        if resolved.limit_exceeded {
+            self.report_file_limit_exceeded();
+            return;
+        }
+
+        for root in &resolved.roots {
+            self.log(ScanEvent::Root(root.clone()));
+        }
+
+        let files: Vec<_> = resolved
+            .files
+            .into_iter()
+            .filter(|path| !self.is_generated_output(path))
+            .collect();
+
+        self.files.extend(files);
+        self.globs.extend(resolved.globs);
+        self.dirs.extend(resolved.dirs);
+        self.explicit_patterns.extend(resolved.patterns);
 
         // Re-optimize the globs to reduce the number of patterns we have to scan.
         self.globs = optimize_patterns(&self.globs);
+
+        if self.options.deterministic {
+            self.sort_sources();
+        }
+
+        self.log(ScanEvent::SourcesResolved {
+            files: self.files.len(),
+            globs: self.globs.len(),
+            dirs: self.dirs.len(),
+        });
+    }
+}
+
+// Remove the `_` (a space) around commas in arbitrary values, e.g.: `grid-cols-[minmax(0,_1fr)]`
+// becomes `grid-cols-[minmax(0,1fr)]`. Both versions generate the same CSS. Escaped underscores
+// and underscores inside of strings are kept, e.g.: `content-['a,_b']`.
+fn canonicalize_arbitrary_values(candidate: &str) -> Cow<'_, str> {
+    if !candidate.contains(",_") && !candidate.contains("_,") {
+        return Cow::Borrowed(candidate);
+    }
+
+    let bytes = candidate.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut depth = 0usize;
+    let mut quote = None;
+
+    for (idx, &c) in bytes.iter().enumerate() {
+        let prev = idx.checked_sub(1).map(|idx| bytes[idx]);
+
+        match (quote, c) {
+            (Some(q), _) if c == q && prev != Some(b'\\') => quote = None,
+            (Some(_), _) => {}
+            (None, b'\'' | b'"') if depth > 0 => quote = Some(c),
+            (None, b'[') => depth += 1,
+            (None, b']') => depth = depth.saturating_sub(1),
+            (None, b'_') if depth > 0 && prev != Some(b'\\') => {
+                // Find the closest characters that are not underscores
+                let before = bytes[..idx].iter().rev().find(|x| **x != b'_');
+                let after = bytes[idx + 1..].iter().find(|x| **x != b'_');
+
+                if before == Some(&b',') || after == Some(&b',') {
+                    continue;
+                }
+            }
+            _ => {}
+        }
+
+        result.push(c);
+    }
+
+    // Only ASCII underscores were removed, so the result is still valid UTF-8
+    Cow::Owned(String::from_utf8(result).unwrap_or_else(|_| candidate.to_owned()))
+}
+
+// All candidates in the content, together with their byte offset.
+fn candidates_with_positions(content: &[u8]) -> Vec<(String, usize)> {
+    let original_content = content;
+
+    // Workaround for legacy upgrades:
+    //
+    // `-[]` won't parse in the new parser (`[…]` must contain _something_), but we do need it
+    // for people using `group-[]` (which we will later replace with `in-[.group]` instead).
+    let content = content.replace("-[]", "XYZ");
+    let offset = content.as_ptr() as usize;
+
+    let mut extractor = Extractor::new(&content[..]);
+
+    extractor
+        .extract()
+        .into_par_iter()
+        .flat_map(|extracted| match extracted {
+            Extracted::Candidate(s) => {
+                let i = s.as_ptr() as usize - offset;
+                let original = &original_content[i..i + s.len()];
+                if original.contains_str("-[]") {
+                    return Some(unsafe { (String::from_utf8_unchecked(original.to_vec()), i) });
+                }
+
+                // SAFETY: When we parsed the candidates, we already guaranteed that the byte
+                // slices are valid, therefore we don't have to re-check here when we want to
+                // convert it back to a string.
+                Some(unsafe { (String::from_utf8_unchecked(s.to_vec()), i) })
+            }
+
+            _ => None,
+        })
+        .collect()
+}
+
+// Whether the path matches any of the given full glob patterns.
+fn matches_any_pattern(patterns: &[String], path: &std::path::Path) -> bool {
+    if patterns.is_empty() {
+        return false;
+    }
+
+    let Some(path) = path.to_str() else {
+        return false;
+    };
+
+    let path = path.replace('\\', "/");
+
+    patterns.iter().any(|pattern| glob_match(pattern, &path))
+}
+
+// Decompress the content of compressed files, e.g.: `index.html.br`. The extension of the
+// compressed file itself is returned, e.g.: `html`, so the right pre-processor is used.
+#[cfg(feature = "compression")]
+fn decompress<'a>(
+    file: &std::path::Path,
+    content: Vec<u8>,
+    extension: Cow<'a, str>,
+) -> Option<(Vec<u8>, Cow<'a, str>)> {
+    if extension != "br" {
+        return Some((content, extension));
+    }
+
+    let mut decompressed = vec![];
+    if let Err(e) = brotli::BrotliDecompress(&mut &content[..], &mut decompressed) {
+        event!(tracing::Level::ERROR, "Failed to decompress file: {:?}", e);
+        return None;
+    }
+
+    let extension = file
+        .file_stem()
+        .map(std::path::Path::new)
+        .and_then(|stem| stem.extension())
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+
+    Some((decompressed, extension.into()))
+}
+
+#[cfg(not(feature = "compression"))]
+fn decompress<'a>(
+    _file: &std::path::Path,
+    content: Vec<u8>,
+    extension: Cow<'a, str>,
+) -> Option<(Vec<u8>, Cow<'a, str>)> {
+    Some((content, extension))
+}
+
+// Relative source bases are resolved against the `base` option, if any.
+fn resolve_base(base: Option<&str>, source_base: &str) -> PathBuf {
+    match base {
+        Some(base) if std::path::Path::new(source_base).is_relative() => {
+            PathBuf::from(base).join(source_base)
+        }
+        _ => PathBuf::from(source_base),
+    }
+}
+
+// The extension used to pick the pre-processor for a file. This is the regular extension, except
+// for compound extensions like `.blade.php`.
+fn content_extension(path: &std::path::Path) -> Option<Cow<'_, str>> {
+    let extension = path.extension()?.to_string_lossy();
+
+    let is_blade = extension == "php"
+        && path
+            .file_stem()
+            .and_then(|stem| std::path::Path::new(stem).extension())
+            .is_some_and(|ext| ext == "blade");
+
+    if is_blade {
+        Some("blade.php".into())
+    } else {
+        Some(extension)
+    }
+}
+
+// Canonicalize a path that doesn't necessarily exist yet, e.g.: a file that is generated later on.
+fn canonicalize_lossy(path: &std::path::Path) -> PathBuf {
+    if let Ok(path) = dunce::canonicalize(path) {
+        return path;
+    }
+
+    match (
+        path.parent()
+            .and_then(|parent| dunce::canonicalize(parent).ok()),
+        path.file_name(),
+    ) {
+        (Some(parent), Some(file_name)) => parent.join(file_name),
+        _ => path.to_path_buf(),
     }
 }
 
-fn read_changed_content(c: ChangedContent) -> Option<Vec<u8>> {
+fn read_blocklist(path: &std::path::Path) -> std::io::Result<FxHashSet<String>> {
+    let content = fs::read_to_string(path)?;
+
+    Ok(content
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_owned())
+        .collect())
+}
+
+fn read_changed_content(
+    c: ChangedContent,
+    pre_processors: &PreProcessors,
+    content_reader: &dyn ContentReader,
+) -> Option<Vec<u8>> {
     let (content, extension) = match c {
-        ChangedContent::File(file, extension) => match std::fs::read(&file) {
-            Ok(content) => (content, extension),
+        ChangedContent::File(file, extension) => match content_reader.read(&file) {
+            Ok(content) => decompress(&file, content, extension)?,
             Err(e) => {
                 event!(tracing::Level::ERROR, "Failed to read file: {:?}", e);
                 return None;
@@ -459,44 +2204,206 @@ fn read_changed_content(c: ChangedContent) -> Option<Vec<u8>> {
         },
 
         ChangedContent::Content(contents, extension) => (contents.into_bytes(), extension),
+
+        ChangedContent::Concatenated(contents, _, extension) => (contents.into_bytes(), extension),
     };
 
-    Some(pre_process_input(&content, &extension))
+    Some(pre_processors.process(&content, &extension))
+}
+
+/// Extract all candidates from a single string, pre-processed based on the `extension`. The
+/// candidates are sorted and unique.
+pub fn extract_from_string(content: &str, extension: &str) -> Vec<String> {
+    parse_all_blobs(vec![pre_process_input(content.as_bytes(), extension)])
+}
+
+/// Whether the extractor picks up the whole string as a single candidate, e.g.: to lint the
+/// entries of a safelist. `flex` and `[color:red]` are valid, `p 4` is not.
+pub fn is_valid_candidate(candidate: &str) -> bool {
+    Extractor::new(candidate.as_bytes())
+        .extract()
+        .into_iter()
+        .any(|extracted| matches!(extracted, Extracted::Candidate(bytes) if bytes == candidate.as_bytes()))
+}
+
+/// Scan multiple independent projects in parallel, e.g.: all packages in a monorepo. Every project
+/// gets its own `Scanner`, and the candidates are returned in the same order as the projects.
+pub fn scan_many(projects: Vec<Vec<GlobEntry>>) -> Vec<Vec<String>> {
+    projects
+        .into_par_iter()
+        .map(|sources| Scanner::new(Some(sources)).scan())
+        .collect()
+}
+
+/// The content of a file after the pre-processor for its extension ran, e.g.: to see why a class
+/// isn't detected. Returns `None` when the file can't be read.
+pub fn preprocess_file(path: &std::path::Path) -> Option<String> {
+    let extension = content_extension(path).unwrap_or_default().into_owned();
+    let content = read_changed_content(
+        ChangedContent::File(path.to_path_buf(), extension.into()),
+        &PreProcessors::default(),
+        &FsContentReader,
+    )?;
+
+    Some(String::from_utf8_lossy(&content).into_owned())
 }
 
 pub fn pre_process_input(content: &[u8], extension: &str) -> Vec<u8> {
-    use crate::extractor::pre_processors::*;
+    match PreProcessorKind::for_extension(extension) {
+        Some(kind) => kind.process(content),
+        None => content.to_vec(),
+    }
+}
 
-    match extension {
-        "clj" | "cljs" | "cljc" => Clojure.process(content),
-        "cshtml" | "razor" => Razor.process(content),
-        "haml" => Haml.process(content),
-        "json" => Json.process(content),
-        "pug" => Pug.process(content),
-        "rb" | "erb" => Ruby.process(content),
-        "slim" => Slim.process(content),
-        "svelte" => Svelte.process(content),
-        "vue" => Vue.process(content),
-        _ => content.to_vec(),
+/// Pre-process the input using a custom extension to pre-processor mapping instead of the
+/// default one.
+pub fn pre_process_input_with(
+    content: &[u8],
+    extension: &str,
+    pre_processors: &FxHashMap<String, PreProcessorKind>,
+) -> Vec<u8> {
+    match pre_processors.get(extension) {
+        Some(kind) => kind.process(content),
+        None => content.to_vec(),
     }
 }
 
 #[tracing::instrument(skip_all)]
-fn read_all_files(changed_content: Vec<ChangedContent>) -> Vec<Vec<u8>> {
+fn read_all_files(
+    changed_content: Vec<ChangedContent>,
+    pre_processors: &PreProcessors,
+    content_reader: &dyn ContentReader,
+    on_progress: Option<&ProgressCallback>,
+) -> Vec<Vec<u8>> {
+    read_all_files_by_source(changed_content, pre_processors, content_reader, on_progress)
+        .into_iter()
+        .filter_map(|(_, content)| content)
+        .collect()
+}
+
+// Read all files, while keeping track of the file each piece of content came from. Files that
+// could not be read are kept with `None` as their content.
+fn read_all_files_by_source(
+    changed_content: Vec<ChangedContent>,
+    pre_processors: &PreProcessors,
+    content_reader: &dyn ContentReader,
+    on_progress: Option<&ProgressCallback>,
+) -> Vec<(Option<PathBuf>, Option<Vec<u8>>)> {
     event!(
         tracing::Level::INFO,
         "Reading {:?} file(s)",
         changed_content.len()
     );
 
+    // Every segment of concatenated content is read as its own source
+    let changed_content: Vec<_> = changed_content
+        .into_iter()
+        .flat_map(|changed_content| match changed_content {
+            ChangedContent::File(path, extension) => {
+                vec![(Some(path.clone()), ChangedContent::File(path, extension))]
+            }
+            ChangedContent::Content(contents, extension) => {
+                vec![(None, ChangedContent::Content(contents, extension))]
+            }
+            ChangedContent::Concatenated(contents, separator, extension) => {
+                split_segments(&contents, &separator)
+                    .into_iter()
+                    .map(|(name, segment)| {
+                        (
+                            name,
+                            ChangedContent::Content(segment.to_owned(), extension.clone()),
+                        )
+                    })
+                    .collect()
+            }
+        })
+        .collect();
+
+    let total = changed_content.len();
+    let done = sync::atomic::AtomicUsize::new(0);
+
     changed_content
         .into_par_iter()
-        .filter_map(read_changed_content)
+        .map(|(source, changed_content)| {
+            let content = read_changed_content(changed_content, pre_processors, content_reader);
+
+            if let Some(on_progress) = on_progress {
+                let done = done.fetch_add(1, sync::atomic::Ordering::Relaxed) + 1;
+                on_progress.call(ScanProgress { done, total });
+            }
+
+            (source, content)
+        })
         .collect()
 }
 
+// Split concatenated content into its named segments. Content before the first separator doesn't
+// have a name.
+fn split_segments<'a>(content: &'a str, separator: &str) -> Vec<(Option<PathBuf>, &'a str)> {
+    if separator.is_empty() {
+        return vec![(None, content)];
+    }
+
+    let mut parts = content.split(separator);
+    let mut segments = vec![];
+
+    if let Some(head) = parts.next().filter(|head| !head.trim().is_empty()) {
+        segments.push((None, head));
+    }
+
+    for part in parts {
+        let (name, segment) = part.split_once('\n').unwrap_or((part, ""));
+        let name = name.trim();
+
+        segments.push(((!name.is_empty()).then(|| PathBuf::from(name)), segment));
+    }
+
+    segments
+}
+
 #[tracing::instrument(skip_all)]
 fn parse_all_blobs(blobs: Vec<Vec<u8>>) -> Vec<String> {
+    extract_all_blobs(blobs, |extracted| match extracted {
+        Extracted::Candidate(bytes) => Some(bytes),
+        Extracted::CssVariable(bytes) => Some(bytes),
+    })
+}
+
+// Same as `parse_all_blobs`, but duplicates are counted instead of collapsed.
+fn count_all_blobs(blobs: Vec<Vec<u8>>) -> Vec<(String, usize)> {
+    blobs
+        .par_iter()
+        .flat_map(|blob| blob.par_split(|x| *x == b'\n'))
+        .map(|blob| {
+            let mut counts: FxHashMap<&[u8], usize> = FxHashMap::default();
+
+            for extracted in Extractor::new(blob).extract() {
+                let bytes = match extracted {
+                    Extracted::Candidate(bytes) => bytes,
+                    Extracted::CssVariable(bytes) => bytes,
+                };
+
+                *counts.entry(bytes).or_default() += 1;
+            }
+
+            counts
+        })
+        .reduce(FxHashMap::default, |mut a, b| {
+            for (bytes, count) in b {
+                *a.entry(bytes).or_default() += count;
+            }
+            a
+        })
+        .into_iter()
+        .map(|(s, count)| (unsafe { String::from_utf8_unchecked(s.to_vec()) }, count))
+        .collect()
+}
+
+// Extract all blobs, but only keep the extracted values for which `filter` returns a value.
+fn extract_all_blobs<F>(blobs: Vec<Vec<u8>>, filter: F) -> Vec<String>
+where
+    F: for<'a> Fn(Extracted<'a>) -> Option<&'a [u8]> + Sync,
+{
     let mut result: Vec<_> = blobs
         .par_iter()
         .flat_map(|blob| blob.par_split(|x| *x == b'\n'))
@@ -510,12 +2417,9 @@ fn parse_all_blobs(blobs: Vec<Vec<u8>>) -> Vec<String> {
                 return None;
             }
 
-            Some(FxHashSet::from_iter(extracted.into_iter().map(
-                |x| match x {
-                    Extracted::Candidate(bytes) => bytes,
-                    Extracted::CssVariable(bytes) => bytes,
-                },
-            )))
+            Some(FxHashSet::from_iter(
+                extracted.into_iter().filter_map(&filter),
+            ))
         })
         .reduce(Default::default, |mut a, b| {
             a.extend(b);
@@ -534,7 +2438,10 @@ fn parse_all_blobs(blobs: Vec<Vec<u8>>) -> Vec<String> {
 
 #[cfg(test)]
 mod tests {
-    use crate::Scanner;
+    use crate::{
+        extract_from_string, is_valid_candidate, ChangedContent, ResumeToken, ScanOptions, Scanner,
+    };
+    use std::path::PathBuf;
 
     #[test]
     fn test_positions() {
@@ -575,4 +2482,98 @@ mod tests {
             assert_eq!(candidates, expected);
         }
     }
+
+    #[test]
+    fn test_resume_token_round_trip() {
+        let mut token = ResumeToken::default();
+        token.completed_dirs.insert(PathBuf::from("/project/src"));
+        token
+            .completed_dirs
+            .insert(PathBuf::from("/project/a\nc flex"));
+        token
+            .completed_dirs
+            .insert(PathBuf::from("/project/100%/ünïcode"));
+        token.candidates.insert("flex".to_owned());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            token
+                .completed_dirs
+                .insert(PathBuf::from(std::ffi::OsStr::from_bytes(
+                    b"/project/\xff\xfe",
+                )));
+        }
+
+        let encoded = token.encode();
+
+        // Paths can't inject lines of their own, e.g.: a `c flex` candidate
+        assert_eq!(
+            encoded.lines().count(),
+            token.completed_dirs.len() + token.candidates.len()
+        );
+        assert_eq!(ResumeToken::decode(&encoded), token);
+    }
+
+    #[test]
+    fn test_is_valid_candidate() {
+        for (candidate, expected) in [
+            ("flex", true),
+            ("[color:red]", true),
+            ("hover:bg-red-500/50", true),
+            ("p 4", false),
+            (" flex", false),
+            ("", false),
+        ] {
+            assert_eq!(is_valid_candidate(candidate), expected, "{candidate}");
+        }
+    }
+
+    #[test]
+    fn test_extract_from_string() {
+        for (content, extension, expected) in [
+            (
+                r#"<div class="flex underline flex"></div>"#,
+                "html",
+                vec!["class", "flex", "underline"],
+            ),
+            (
+                ".bg-red-500.px-2.5 Hello",
+                "pug",
+                vec!["bg-red-500", "px-2.5"],
+            ),
+            (
+                "%div.flex.items-center{class: 'p-4'}",
+                "haml",
+                vec!["class", "flex", "items-center", "p-4"],
+            ),
+            ("", "html", vec![]),
+        ] {
+            assert_eq!(extract_from_string(content, extension), expected);
+        }
+    }
+
+    #[test]
+    fn test_canonicalize_arbitrary_values() {
+        let mut scanner = Scanner::with_options(ScanOptions {
+            canonicalize_arbitrary_values: true,
+            ..Default::default()
+        });
+
+        let candidates = scanner.scan_content(vec![ChangedContent::Content(
+            r#"<div class="grid-cols-[minmax(0,_1fr)] grid-cols-[minmax(0,1fr)] grid-cols-[1fr_2fr] content-['a,_b'] bg-[a\_,b]">"#.into(),
+            "html".into(),
+        )]);
+
+        assert_eq!(
+            candidates,
+            vec![
+                "bg-[a\\_,b]",
+                "class",
+                "content-['a,_b']",
+                "grid-cols-[1fr_2fr]",
+                "grid-cols-[minmax(0,1fr)]",
+            ]
+        );
+    }
 }
@@ -0,0 +1,137 @@
+/// Heuristics to drop extracted candidates that are very unlikely to be utilities, e.g.:
+/// variable names and imported symbols in JavaScript files.
+///
+/// This can drop custom utilities without any structure (e.g.: `@utility tab`), which is why it
+/// has to be enabled explicitly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoiseFilter {
+    /// Drop candidates without any structure, e.g.: `useState`. Candidates are kept when they
+    /// contain a `-`, `:`, `[`, `(`, `/` or `!`, or when they are a known utility without a
+    /// value, e.g.: `flex`.
+    Loose,
+
+    /// Same as [NoiseFilter::Loose], but also drops candidates with uppercase characters outside
+    /// of arbitrary values, e.g.: `onClick-handler`.
+    Strict,
+}
+
+/// Utilities that are valid without a value or variant.
+const BARE_UTILITIES: &[&str] = &[
+    "absolute",
+    "antialiased",
+    "block",
+    "blur",
+    "border",
+    "capitalize",
+    "collapse",
+    "container",
+    "contents",
+    "filter",
+    "fixed",
+    "flex",
+    "flow-root",
+    "grayscale",
+    "grid",
+    "grow",
+    "hidden",
+    "inline",
+    "invert",
+    "invisible",
+    "isolate",
+    "italic",
+    "lowercase",
+    "ordinal",
+    "outline",
+    "overline",
+    "relative",
+    "resize",
+    "ring",
+    "rounded",
+    "sepia",
+    "shadow",
+    "shrink",
+    "static",
+    "sticky",
+    "table",
+    "transform",
+    "transition",
+    "truncate",
+    "underline",
+    "uppercase",
+    "visible",
+];
+
+impl NoiseFilter {
+    /// Whether the candidate is most likely not a utility.
+    pub fn is_noise(&self, candidate: &str) -> bool {
+        let has_structure = candidate
+            .bytes()
+            .any(|x| matches!(x, b'-' | b':' | b'[' | b'(' | b'/' | b'!'));
+
+        if !has_structure && !BARE_UTILITIES.contains(&candidate) {
+            return true;
+        }
+
+        match self {
+            NoiseFilter::Loose => false,
+            NoiseFilter::Strict => has_uppercase_outside_of_brackets(candidate),
+        }
+    }
+}
+
+fn has_uppercase_outside_of_brackets(candidate: &str) -> bool {
+    let mut depth = 0usize;
+
+    for x in candidate.bytes() {
+        match x {
+            b'[' | b'(' => depth += 1,
+            b']' | b')' => depth = depth.saturating_sub(1),
+            x if depth == 0 && x.is_ascii_uppercase() => return true,
+            _ => {}
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NoiseFilter;
+
+    #[test]
+    fn test_noise_filter() {
+        for (candidate, loose, strict) in [
+            // Identifiers
+            ("useState", true, true),
+            ("props", true, true),
+            ("const", true, true),
+            // Bare utilities
+            ("flex", false, false),
+            ("underline", false, false),
+            ("blur", false, false),
+            ("overline", false, false),
+            ("resize", false, false),
+            // Only valid with a value, e.g.: `contain-none`
+            ("contain", true, true),
+            ("diagonal", true, true),
+            // Structured candidates
+            ("px-4", false, false),
+            ("hover:flex", false, false),
+            ("!italic", false, false),
+            ("w-1/2", false, false),
+            ("[color:red]", false, false),
+            ("--my-variable", false, false),
+            // Uppercase characters
+            ("onClick-handler", false, true),
+            ("content-['Hello_World']", false, false),
+            ("bg-(--Brand)", false, false),
+        ] {
+            assert_eq!(NoiseFilter::Loose.is_noise(candidate), loose, "{candidate}");
+            assert_eq!(
+                NoiseFilter::Strict.is_noise(candidate),
+                strict,
+                "{candidate}"
+            );
+        }
+    }
+}
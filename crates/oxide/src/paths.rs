@@ -63,3 +63,58 @@ impl Path {
         Ok(dunce::canonicalize(&self.inner)?.into())
     }
 }
+
+/// Rewrites a path prefix to another prefix, e.g. to translate paths inside of a container to
+/// paths on the host machine.
+///
+/// ```text
+/// from_prefix: /app
+/// to_prefix:   /Users/me/project
+///
+/// /app/src/index.html -> /Users/me/project/src/index.html
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathRemap {
+    pub from_prefix: String,
+    pub to_prefix: String,
+}
+
+impl PathRemap {
+    pub fn apply(&self, path: &str) -> String {
+        let from_prefix = self.from_prefix.trim_end_matches(['/', '\\']);
+        let to_prefix = self.to_prefix.trim_end_matches(['/', '\\']);
+
+        match path.strip_prefix(from_prefix) {
+            // Only remap when the prefix ends on a path segment boundary, otherwise `/app` would
+            // also match `/application`.
+            Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+                format!("{}{}", to_prefix, rest)
+            }
+            _ => path.to_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PathRemap;
+
+    #[test]
+    fn test_path_remap() {
+        let remap = PathRemap {
+            from_prefix: "/app/".into(),
+            to_prefix: "/Users/me/project".into(),
+        };
+
+        for (input, expected) in [
+            ("/app", "/Users/me/project"),
+            ("/app/src/index.html", "/Users/me/project/src/index.html"),
+            // Not on a segment boundary
+            ("/application/index.html", "/application/index.html"),
+            // Different prefix
+            ("/other/index.html", "/other/index.html"),
+        ] {
+            assert_eq!(remap.apply(input), expected);
+        }
+    }
+}
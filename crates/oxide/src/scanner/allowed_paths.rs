@@ -1,5 +1,8 @@
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use ignore::{DirEntry, WalkBuilder};
-use std::{path::Path, sync};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, sync};
 
 static BINARY_EXTENSIONS: sync::LazyLock<Vec<&'static str>> = sync::LazyLock::new(|| {
     include_str!("fixtures/binary-extensions.txt")
@@ -22,9 +25,69 @@ static IGNORED_FILES: sync::LazyLock<Vec<&'static str>> = sync::LazyLock::new(||
         .collect()
 });
 
+/// Generated files that match scanned extensions, but never contain any classes. These can't be
+/// detected by their extension alone, e.g.: `index.d.ts`.
+static DECLARATION_FILE_SUFFIXES: &[&str] = &[".d.ts", ".d.mts", ".d.cts", ".map"];
+
 static IGNORED_CONTENT_DIRS: sync::LazyLock<Vec<&'static str>> =
     sync::LazyLock::new(|| vec![".git"]);
 
+/// Options that influence which ignore files are used while walking a directory.
+#[derive(Debug, Clone, Default)]
+pub struct WalkOptions {
+    /// Git repositories and ignore files outside of this directory are not considered
+    pub project_root: Option<PathBuf>,
+
+    /// Directory that contains the `.git` directory, if already known. This avoids looking for it
+    /// in all parent directories every time a directory is walked.
+    pub git_root: Option<PathBuf>,
+
+    /// Honor `.npmignore` files, e.g.: to only scan the files a package actually ships.
+    /// `.gitignore` files still apply as well.
+    pub npmignore: bool,
+
+    /// Custom predicate consulted for every file and directory on top of the ignore files
+    pub path_filter: Option<PathFilter>,
+
+    /// Hidden files and directories are only walked inside of these directories. When not set,
+    /// hidden files are walked everywhere.
+    pub hidden_dirs: Option<Vec<PathBuf>>,
+
+    /// Only files with these extensions are picked up by auto source detection. Explicit sources
+    /// are not affected.
+    pub only_extensions: Option<Vec<String>>,
+}
+
+impl WalkOptions {
+    /// Whether the file has one of the `only_extensions`, if any.
+    pub fn allows_extension(&self, path: &Path) -> bool {
+        self.only_extensions
+            .as_ref()
+            .is_none_or(|extensions| has_any_extension(path, extensions))
+    }
+}
+
+/// Custom inclusion logic for the walker. Returning `false` skips the file, or the directory and
+/// everything inside of it.
+#[derive(Clone)]
+pub struct PathFilter(sync::Arc<dyn Fn(&Path) -> bool + Send + Sync>);
+
+impl PathFilter {
+    pub fn new(filter: impl Fn(&Path) -> bool + Send + Sync + 'static) -> Self {
+        Self(sync::Arc::new(filter))
+    }
+
+    pub(crate) fn is_allowed(&self, path: &Path) -> bool {
+        (self.0)(path)
+    }
+}
+
+impl fmt::Debug for PathFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PathFilter").finish()
+    }
+}
+
 #[tracing::instrument(skip_all)]
 pub fn resolve_allowed_paths(root: &Path) -> impl Iterator<Item = DirEntry> {
     // Read the directory recursively with no depth limit
@@ -32,30 +95,59 @@ pub fn resolve_allowed_paths(root: &Path) -> impl Iterator<Item = DirEntry> {
 }
 
 #[tracing::instrument(skip_all)]
-pub fn resolve_paths(root: &Path) -> impl Iterator<Item = DirEntry> {
-    create_walk_builder(root).build().filter_map(Result::ok)
+pub fn resolve_paths(root: &Path, options: &WalkOptions) -> impl Iterator<Item = DirEntry> {
+    create_walk_builder(root, options, |_| true)
+        .build()
+        .filter_map(Result::ok)
 }
 
 pub fn read_dir(root: &Path, depth: Option<usize>) -> impl Iterator<Item = DirEntry> {
-    create_walk_builder(root)
-        .max_depth(depth)
-        .filter_entry(move |entry| match entry.file_type() {
-            Some(file_type) if file_type.is_dir() => match entry.file_name().to_str() {
-                Some(dir) => !IGNORED_CONTENT_DIRS.contains(&dir),
-                None => false,
-            },
-            Some(file_type) if file_type.is_file() || file_type.is_symlink() => {
-                is_allowed_content_path(entry.path())
-            }
-            _ => false,
-        })
-        .build()
-        .filter_map(Result::ok)
+    read_dir_with(
+        root,
+        &WalkOptions::default(),
+        depth,
+        is_allowed_content_path,
+    )
+}
+
+/// Same as [read_dir], but files are only allowed when `is_allowed` returns `true`.
+pub fn read_dir_with(
+    root: &Path,
+    options: &WalkOptions,
+    depth: Option<usize>,
+    is_allowed: impl Fn(&Path) -> bool + Send + Sync + 'static,
+) -> impl Iterator<Item = DirEntry> {
+    create_walk_builder(root, options, move |entry| match entry.file_type() {
+        Some(file_type) if file_type.is_dir() => match entry.file_name().to_str() {
+            Some(dir) => !IGNORED_CONTENT_DIRS.contains(&dir),
+            None => false,
+        },
+        Some(file_type) if file_type.is_file() || file_type.is_symlink() => {
+            is_allowed(entry.path())
+        }
+        _ => false,
+    })
+    .max_depth(depth)
+    .build()
+    .filter_map(Result::ok)
 }
 
-fn create_walk_builder(root: &Path) -> WalkBuilder {
+/// The walker only supports a single entry filter, so additional filtering has to be passed in
+/// through `filter`.
+fn create_walk_builder(
+    root: &Path,
+    options: &WalkOptions,
+    filter: impl Fn(&DirEntry) -> bool + Send + Sync + 'static,
+) -> WalkBuilder {
     let mut builder = WalkBuilder::new(root);
 
+    let mut ignore_files = vec![".gitignore"];
+
+    if options.npmignore {
+        builder.add_custom_ignore_filename(".npmignore");
+        ignore_files.push(".npmignore");
+    }
+
     // Scan hidden files / directories
     builder.hidden(false);
 
@@ -64,10 +156,6 @@ fn create_walk_builder(root: &Path) -> WalkBuilder {
     // are first created and may not be in a git repo yet.
     builder.require_git(false);
 
-    // Don't descend into .git directories inside the root folder
-    // This is necessary when `root` contains the `.git` dir.
-    builder.filter_entry(|entry| entry.file_name() != ".git");
-
     // If we are in a git repo then require it to ensure that only rules within
     // the repo are used. For example, we don't want to consider a .gitignore file
     // in the user's home folder if we're in a git repo.
@@ -98,16 +186,123 @@ fn create_walk_builder(root: &Path) -> WalkBuilder {
     // - my-project/apps/.gitignore
     //
     // Setting the require_git(true) flag conditionally allows us to do this.
-    for parent in root.ancestors() {
-        if parent.join(".git").exists() {
-            builder.require_git(true);
-            break;
+    //
+    // When the `git_root` is known, we don't have to check the file system for it.
+    //
+    // When a `project_root` is known, we don't look any further than that. This way an unrelated
+    // outer repository doesn't influence which files are ignored.
+    let project_root = options
+        .project_root
+        .as_deref()
+        .filter(|project_root| root.starts_with(project_root));
+    let ancestors = root.ancestors().take_while(|parent| match project_root {
+        Some(project_root) => parent.starts_with(project_root),
+        None => true,
+    });
+
+    let git_root = options
+        .git_root
+        .as_deref()
+        .filter(|git_root| root.starts_with(git_root));
+
+    let mut parent_ignores = vec![];
+
+    if git_root.is_some() {
+        builder.require_git(true);
+    }
+
+    // Without a `project_root`, the parent directories are only needed to find the git root
+    if project_root.is_some() || git_root.is_none() {
+        for parent in ancestors {
+            if parent != root {
+                let files: Vec<_> = ignore_files.iter().map(|file| parent.join(file)).collect();
+                parent_ignores.push((parent, files));
+            }
+
+            let is_git_root = match git_root {
+                Some(git_root) => parent == git_root,
+                None => parent.join(".git").exists(),
+            };
+
+            if is_git_root {
+                builder.require_git(true);
+                break;
+            }
         }
     }
 
+    // The ignore files in all parent directories up to the `project_root` have to be applied
+    // explicitly, otherwise all of them would be used. Each of them is rooted at its own
+    // directory, so that anchored patterns like `/dist` keep working.
+    let parent_ignores: Vec<Gitignore> = if project_root.is_some() {
+        builder.parents(false);
+
+        parent_ignores
+            .into_iter()
+            .filter_map(|(parent, files)| build_parent_ignore(parent, &files))
+            .collect()
+    } else {
+        vec![]
+    };
+
+    // Don't descend into .git directories inside the root folder
+    // This is necessary when `root` contains the `.git` dir.
+    let entry_options = options.clone();
+    builder.filter_entry(move |entry| {
+        entry.file_name() != ".git"
+            && is_allowed_entry(&entry_options, entry)
+            && !is_ignored_by_parents(&parent_ignores, entry)
+            && filter(entry)
+    });
+
     builder
 }
 
+// Combines the ignore files of a parent directory, `None` if it doesn't contain any.
+fn build_parent_ignore(parent: &Path, files: &[PathBuf]) -> Option<Gitignore> {
+    let mut builder = GitignoreBuilder::new(parent);
+    let mut has_files = false;
+
+    for file in files.iter().filter(|file| file.is_file()) {
+        has_files |= builder.add(file).is_none();
+    }
+
+    has_files.then(|| builder.build().ok()).flatten()
+}
+
+// Whether the entry is ignored by the ignore files of the parent directories. These are sorted from
+// the nearest parent outwards, so the rules of the closest ignore file win.
+fn is_ignored_by_parents(parent_ignores: &[Gitignore], entry: &DirEntry) -> bool {
+    let is_dir = entry
+        .file_type()
+        .is_some_and(|file_type| file_type.is_dir());
+
+    parent_ignores
+        .iter()
+        .map(|ignore| ignore.matched_path_or_any_parents(entry.path(), is_dir))
+        .find(|matched| !matched.is_none())
+        .is_some_and(|matched| matched.is_ignore())
+}
+
+// Whether the entry is allowed by the `path_filter` and `hidden_dirs` options.
+fn is_allowed_entry(options: &WalkOptions, entry: &DirEntry) -> bool {
+    if let Some(hidden_dirs) = &options.hidden_dirs {
+        let is_hidden = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| name.starts_with('.'));
+
+        if is_hidden && !hidden_dirs.iter().any(|dir| entry.path().starts_with(dir)) {
+            return false;
+        }
+    }
+
+    options
+        .path_filter
+        .as_ref()
+        .is_none_or(|path_filter| path_filter.is_allowed(entry.path()))
+}
+
 pub fn is_allowed_content_path(path: &Path) -> bool {
     // Skip known ignored files
     if path
@@ -120,9 +315,86 @@ pub fn is_allowed_content_path(path: &Path) -> bool {
         return false;
     }
 
+    // Skip type declarations and source maps
+    if is_declaration_file(path) {
+        return false;
+    }
+
     // Skip known ignored extensions
     path.extension()
         .map(|s| s.to_str().unwrap_or_default())
         .map(|ext| !IGNORED_EXTENSIONS.contains(&ext) && !BINARY_EXTENSIONS.contains(&ext))
         .unwrap_or(false)
 }
+
+/// Whether the file is a TypeScript declaration file or a source map, e.g.: `index.d.ts` or
+/// `app.js.map`.
+pub fn is_declaration_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| {
+            DECLARATION_FILE_SUFFIXES
+                .iter()
+                .any(|suffix| name.len() > suffix.len() && name.ends_with(suffix))
+        })
+}
+
+pub fn has_any_extension(path: &Path, extensions: &[String]) -> bool {
+    if extensions.is_empty() {
+        return false;
+    }
+
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| extensions.iter().any(|x| x == ext))
+        .unwrap_or(false)
+}
+
+/// Symlinked files are treated as regular files, even though we don't follow symlinked
+/// directories.
+pub fn is_file_or_symlinked_file(entry: &DirEntry) -> bool {
+    match entry.file_type() {
+        Some(file_type) if file_type.is_file() => true,
+        Some(file_type) if file_type.is_symlink() => entry.path().is_file(),
+        _ => false,
+    }
+}
+
+/// Whether an extension-less file starts with any of the given signatures, e.g.:
+/// `<!DOCTYPE html>`. Leading whitespace is ignored and signatures are matched case-insensitively.
+pub fn matches_content_signature(path: &Path, signatures: &[String]) -> bool {
+    if signatures.is_empty() || path.extension().is_some() {
+        return false;
+    }
+
+    let Some(head) = read_head(path) else {
+        return false;
+    };
+
+    let head = head.trim_ascii_start();
+
+    signatures.iter().any(|signature| {
+        head.get(..signature.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(signature.as_bytes()))
+    })
+}
+
+/// Whether the file is a CSS file generated by Tailwind CSS, based on the license header that is
+/// added to the output, e.g.: `/*! tailwindcss v4.0.0 | MIT License | https://tailwindcss.com */`.
+pub fn is_generated_css(path: &Path) -> bool {
+    if path.extension().is_none_or(|ext| ext != "css") {
+        return false;
+    }
+
+    read_head(path).is_some_and(|head| head.trim_ascii_start().starts_with(b"/*! tailwindcss v"))
+}
+
+// We only need the start of the file to check for signatures
+fn read_head(path: &Path) -> Option<Vec<u8>> {
+    let file = fs::File::open(path).ok()?;
+
+    let mut head = vec![];
+    file.take(1024).read_to_end(&mut head).ok()?;
+
+    Some(head)
+}
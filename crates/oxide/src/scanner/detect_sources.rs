@@ -1,14 +1,33 @@
-use crate::scanner::allowed_paths::{is_allowed_content_path, resolve_allowed_paths};
+use crate::scanner::allowed_paths::{
+    has_any_extension, is_allowed_content_path, is_file_or_symlinked_file, read_dir_with,
+    PathFilter, WalkOptions,
+};
 use crate::GlobEntry;
 use fxhash::FxHashSet;
 use std::cmp::Ordering;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync;
 use walkdir::WalkDir;
 
 #[derive(Debug, Clone)]
 pub struct DetectSources {
     base: PathBuf,
+    options: DetectOptions,
+}
+
+#[derive(Debug, Clone)]
+pub struct DetectOptions {
+    /// Extensions that should never be picked up by auto source detection
+    pub excluded_extensions: Vec<String>,
+
+    /// Stop resolving files once more than this amount of files were found
+    pub max_files: Option<usize>,
+
+    /// Files are only picked up when this returns `true`. Directories are not affected.
+    pub is_allowed: PathFilter,
+
+    /// Which ignore files are used while walking the directory
+    pub walk_options: WalkOptions,
 }
 
 static KNOWN_EXTENSIONS: sync::LazyLock<Vec<&'static str>> = sync::LazyLock::new(|| {
@@ -23,13 +42,20 @@ static KNOWN_EXTENSIONS: sync::LazyLock<Vec<&'static str>> = sync::LazyLock::new
 });
 
 impl DetectSources {
-    pub fn new(base: PathBuf) -> Self {
-        Self { base }
+    pub fn new(base: PathBuf, options: DetectOptions) -> Self {
+        Self { base, options }
     }
 
     pub fn detect(&self) -> (Vec<PathBuf>, Vec<GlobEntry>, Vec<PathBuf>) {
         let (files, dirs) = self.resolve_files();
-        let globs = self.resolve_globs(&dirs);
+
+        // Fast path for flat projects. Only sub-directories of the base can become glob
+        // directories, so there is no need to walk the directory again.
+        let globs = if dirs.iter().all(|dir| *dir == self.base) {
+            vec![]
+        } else {
+            self.resolve_globs(&dirs)
+        };
 
         (files, globs, dirs)
     }
@@ -38,13 +64,28 @@ impl DetectSources {
         let mut files: Vec<PathBuf> = vec![];
         let mut dirs: Vec<PathBuf> = vec![];
 
-        for entry in resolve_allowed_paths(&self.base) {
+        let is_allowed = self.options.is_allowed.clone();
+        let is_allowed = move |path: &Path| is_allowed.is_allowed(path);
+
+        for entry in read_dir_with(&self.base, &self.options.walk_options, None, is_allowed) {
             let Some(file_type) = entry.file_type() else {
                 continue;
             };
 
-            if file_type.is_file() {
+            if is_file_or_symlinked_file(&entry) {
+                if has_any_extension(entry.path(), &self.options.excluded_extensions) {
+                    continue;
+                }
+
                 files.push(entry.into_path());
+
+                if self
+                    .options
+                    .max_files
+                    .is_some_and(|max_files| files.len() > max_files)
+                {
+                    break;
+                }
             } else if file_type.is_dir() {
                 dirs.push(entry.into_path());
             }
@@ -199,6 +240,11 @@ impl DetectSources {
             }
         }
 
+        // Excluded extensions should not be watched at all, so we drop them from the globs.
+        for extension in &self.options.excluded_extensions {
+            found_extensions.remove(extension);
+        }
+
         let mut extension_list = found_extensions.into_iter().collect::<Vec<_>>();
 
         extension_list.sort();
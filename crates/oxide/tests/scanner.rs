@@ -1,10 +1,15 @@
 #[cfg(test)]
 mod scanner {
+    use std::ops::ControlFlow;
     use std::process::Command;
+    use std::sync::{Arc, Mutex};
     use std::thread::sleep;
     use std::time::Duration;
     use std::{fs, path};
 
+    use fxhash::FxHashSet;
+    use tailwindcss_oxide::extractor::pre_processors::PreProcessorKind;
+    use tailwindcss_oxide::scanner::allowed_paths::PathFilter;
     use tailwindcss_oxide::*;
     use tempfile::tempdir;
 
@@ -22,6 +27,21 @@ mod scanner {
         }
     }
 
+    // Create a git repository with the given files, and a source that auto detects all of them
+    fn create_project(paths_with_content: &[(&str, &str)]) -> (path::PathBuf, GlobEntry) {
+        let dir = tempdir().unwrap().into_path();
+        let _ = Command::new("git").arg("init").current_dir(&dir).output();
+
+        create_files_in(&dir, paths_with_content);
+
+        let source = GlobEntry {
+            base: dir.to_string_lossy().to_string(),
+            pattern: "**/*".to_owned(),
+        };
+
+        (dir, source)
+    }
+
     fn scan_with_globs(
         paths_with_content: &[(&str, &str)],
         globs: Vec<&str>,
@@ -400,6 +420,33 @@ mod scanner {
         );
     }
 
+    #[test]
+    fn it_should_scan_explicitly_sourced_files_with_binary_extensions() {
+        // `.bin` files are ignored by auto source detection, but explicit sources are text
+        let (paths, candidates) = scan_with_globs(
+            &[
+                (
+                    "do-include-me.bin",
+                    "<div class=\"flex underline\"></div> content-['do-include-me.bin']",
+                ),
+                ("dont-include-me.bin", "content-['dont-include-me.bin']"),
+            ],
+            vec!["./do-include-me.bin"],
+        );
+
+        assert!(paths.contains(&"do-include-me.bin".to_owned()));
+        assert!(!paths.contains(&"dont-include-me.bin".to_owned()));
+        assert_eq!(
+            candidates,
+            vec![
+                "class",
+                "content-['do-include-me.bin']",
+                "flex",
+                "underline"
+            ]
+        );
+    }
+
     #[test]
     fn it_should_scan_content_paths() {
         let candidates = scan_with_globs(
@@ -415,6 +462,88 @@ mod scanner {
         assert_eq!(candidates, vec!["content-['foo.styl']"]);
     }
 
+    #[test]
+    fn it_should_scan_ignored_extensions_recursively_when_explicitly_allowed() {
+        let (paths, candidates) = scan_with_globs(
+            &[
+                ("index.html", "content-['index.html']"),
+                ("root.styl", "content-['root.styl']"),
+                ("src/a.styl", "content-['src/a.styl']"),
+                ("src/nested/b.styl", "content-['src/nested/b.styl']"),
+                (
+                    "src/nested/deeper/c.styl",
+                    "content-['src/nested/deeper/c.styl']",
+                ),
+                // Other ignored extensions should still be ignored
+                ("src/nested/d.less", "content-['src/nested/d.less']"),
+            ],
+            vec!["**/*.styl"],
+        );
+
+        assert_eq!(
+            candidates,
+            vec![
+                "content-['index.html']",
+                "content-['root.styl']",
+                "content-['src/a.styl']",
+                "content-['src/nested/b.styl']",
+                "content-['src/nested/deeper/c.styl']",
+            ]
+        );
+
+        assert!(paths.contains(&"{*,**/*.styl}".to_owned()));
+    }
+
+    #[test]
+    fn it_should_pick_up_new_ignored_extensions_recursively_when_explicitly_allowed() {
+        let (dir, _) = create_project(&[
+            ("index.html", "content-['index.html']"),
+            ("src/nested/a.styl", "content-['src/nested/a.styl']"),
+        ]);
+
+        let base = dir.to_string_lossy().to_string();
+        let mut scanner = Scanner::new(Some(vec![
+            GlobEntry {
+                base: base.clone(),
+                pattern: "**/*".to_owned(),
+            },
+            GlobEntry {
+                base: base.clone(),
+                pattern: "**/*.styl".to_owned(),
+            },
+        ]));
+
+        assert_eq!(
+            scanner.scan(),
+            vec!["content-['index.html']", "content-['src/nested/a.styl']"]
+        );
+
+        // Ensure the directory mtimes change
+        sleep(Duration::from_millis(100));
+
+        create_files_in(
+            &dir,
+            &[
+                ("src/nested/b.styl", "content-['src/nested/b.styl']"),
+                (
+                    "src/nested/deeper/c.styl",
+                    "content-['src/nested/deeper/c.styl']",
+                ),
+                ("src/nested/d.less", "content-['src/nested/d.less']"),
+            ],
+        );
+
+        assert_eq!(
+            scanner.scan(),
+            vec![
+                "content-['index.html']",
+                "content-['src/nested/a.styl']",
+                "content-['src/nested/b.styl']",
+                "content-['src/nested/deeper/c.styl']",
+            ]
+        );
+    }
+
     #[test]
     fn it_should_scan_next_dynamic_folders() {
         let candidates = scan_with_globs(
@@ -748,4 +877,2226 @@ mod scanner {
             ]
         );
     }
+
+    #[test]
+    fn it_should_report_progress_for_every_processed_file() {
+        let (_, source) = create_project(&[
+            ("index.html", "content-['index.html']"),
+            ("a.html", "content-['a.html']"),
+            ("nested/b.html", "content-['nested/b.html']"),
+        ]);
+
+        let progress = Arc::new(Mutex::new(vec![]));
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![source],
+            on_progress: Some(ProgressCallback::new({
+                let progress = progress.clone();
+                move |p| progress.lock().unwrap().push(p)
+            })),
+            ..Default::default()
+        });
+
+        scanner.scan();
+
+        let mut reported = progress.lock().unwrap().clone();
+        reported.sort_by_key(|p| p.done);
+
+        assert_eq!(
+            reported,
+            vec![
+                ScanProgress { done: 1, total: 3 },
+                ScanProgress { done: 2, total: 3 },
+                ScanProgress { done: 3, total: 3 },
+            ]
+        );
+
+        // Scanning additional content reports progress for the new batch only
+        progress.lock().unwrap().clear();
+
+        scanner.scan_content(vec![
+            ChangedContent::Content("flex".to_owned(), "html".into()),
+            ChangedContent::Content("block".to_owned(), "html".into()),
+        ]);
+
+        let mut reported = progress.lock().unwrap().clone();
+        reported.sort_by_key(|p| p.done);
+
+        assert_eq!(
+            reported,
+            vec![
+                ScanProgress { done: 1, total: 2 },
+                ScanProgress { done: 2, total: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_remap_the_prefix_of_files_and_globs() {
+        let (dir, _) = create_project(&[("index.html", ""), ("src/a.html", "")]);
+
+        let base = dunce::canonicalize(&dir)
+            .unwrap()
+            .to_string_lossy()
+            .to_string();
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![GlobEntry {
+                base: base.clone(),
+                pattern: "**/*".to_owned(),
+            }],
+            path_remap: Some(paths::PathRemap {
+                from_prefix: base.clone(),
+                to_prefix: "/host/project".to_owned(),
+            }),
+            ..Default::default()
+        });
+
+        let mut files = scanner.get_files();
+        files.sort();
+
+        assert_eq!(
+            files,
+            vec!["/host/project/index.html", "/host/project/src/a.html"]
+        );
+
+        let mut globs = scanner
+            .get_globs()
+            .into_iter()
+            .map(|glob| glob.base.replace('\\', "/"))
+            .collect::<Vec<_>>();
+        globs.sort();
+
+        assert_eq!(globs, vec!["/host/project", "/host/project/src"]);
+    }
+
+    #[test]
+    fn it_should_scan_for_css_variables_only() {
+        let (_, source) = create_project(&[
+            (
+                "index.html",
+                r#"<div class="flex" style="color: var(--brand-color)"></div>"#,
+            ),
+            (
+                "src/app.tsx",
+                r#"<div className="p-[var(--gap)] text-(--brand-color)" />"#,
+            ),
+        ]);
+
+        let mut scanner = Scanner::new(Some(vec![source]));
+
+        assert_eq!(scanner.scan_variables(), vec!["--brand-color", "--gap"]);
+
+        // The regular scan still contains both candidates and variables
+        let candidates = scanner.scan();
+        assert!(candidates.contains(&"flex".to_owned()));
+        assert!(candidates.contains(&"--brand-color".to_owned()));
+    }
+
+    #[test]
+    fn it_should_not_detect_excluded_extensions() {
+        let (_, source) = create_project(&[
+            ("index.html", "content-['index.html']"),
+            ("README.md", "content-['README.md']"),
+            ("src/a.html", "content-['src/a.html']"),
+            ("src/b.md", "content-['src/b.md']"),
+        ]);
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![source],
+            excluded_extensions: vec!["md".to_owned()],
+            ..Default::default()
+        });
+
+        let candidates = scanner.scan();
+        assert_eq!(
+            candidates,
+            vec!["content-['index.html']", "content-['src/a.html']"]
+        );
+
+        assert!(scanner
+            .get_files()
+            .iter()
+            .all(|file| !file.ends_with(".md")));
+
+        for glob in scanner.get_globs() {
+            let extensions = glob
+                .pattern
+                .rsplit_once('{')
+                .map(|(_, extensions)| extensions.trim_end_matches('}'))
+                .unwrap_or_default();
+
+            assert!(!extensions.split(',').any(|extension| extension == "md"));
+        }
+    }
+
+    #[test]
+    fn it_should_estimate_the_memory_usage_of_candidates() {
+        let mut scanner = Scanner::new(None);
+
+        let mut previous = scanner.candidate_memory_estimate();
+        assert_eq!(previous, 0);
+
+        for i in 0..10 {
+            let content = (0..100)
+                .map(|j| format!("content-['{}-{}']", i, j))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            scanner.scan_content(vec![ChangedContent::Content(content, "html".into())]);
+
+            let current = scanner.candidate_memory_estimate();
+            assert!(current > previous);
+            previous = current;
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn it_should_scan_symlinked_files() {
+        let (dir, _) = create_project(&[
+            ("shared/header.html", "content-['shared/header.html']"),
+            ("app/index.html", "content-['app/index.html']"),
+            ("other/footer.styl", "content-['other/footer.styl']"),
+        ]);
+
+        std::os::unix::fs::symlink(
+            dir.join("shared/header.html"),
+            dir.join("app/shared-header.html"),
+        )
+        .unwrap();
+        std::os::unix::fs::symlink(dir.join("other/footer.styl"), dir.join("app/footer.styl"))
+            .unwrap();
+
+        let app = dir.join("app").to_string_lossy().to_string();
+
+        // Auto source detection
+        let candidates = Scanner::new(Some(vec![GlobEntry {
+            base: app.clone(),
+            pattern: "**/*".to_owned(),
+        }]))
+        .scan();
+
+        assert_eq!(
+            candidates,
+            vec![
+                "content-['app/index.html']",
+                "content-['shared/header.html']"
+            ]
+        );
+
+        // Explicit glob
+        let candidates = Scanner::new(Some(vec![GlobEntry {
+            base: app.clone(),
+            pattern: "*.styl".to_owned(),
+        }]))
+        .scan();
+
+        assert_eq!(candidates, vec!["content-['other/footer.styl']"]);
+    }
+
+    #[test]
+    fn it_should_find_the_sources_of_a_candidate() {
+        let (dir, source) = create_project(&[
+            ("index.html", "<div class='grid-cols-7 flex'></div>"),
+            ("src/a.html", "<div class='grid-cols-7 underline'></div>"),
+            ("src/b.html", "<div class='flex'></div>"),
+        ]);
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![source],
+            provenance: true,
+            ..Default::default()
+        });
+
+        scanner.scan();
+
+        let sources_for = |scanner: &Scanner, candidate: &str| -> Vec<String> {
+            scanner
+                .source_for_candidate(candidate)
+                .iter()
+                .map(|path| {
+                    let path = path.strip_prefix(&dir).unwrap();
+                    path.to_string_lossy().replace('\\', "/")
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            sources_for(&scanner, "grid-cols-7"),
+            vec!["index.html", "src/a.html"]
+        );
+        assert_eq!(sources_for(&scanner, "underline"), vec!["src/a.html"]);
+        assert!(sources_for(&scanner, "unknown").is_empty());
+
+        // Removing the candidate from a file should prune it from the lookup
+        fs::write(dir.join("src/a.html"), "<div class='underline'></div>").unwrap();
+        scanner.scan_content(vec![ChangedContent::File(
+            dir.join("src/a.html"),
+            "html".into(),
+        )]);
+
+        assert_eq!(sources_for(&scanner, "grid-cols-7"), vec!["index.html"]);
+        assert_eq!(sources_for(&scanner, "underline"), vec!["src/a.html"]);
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn it_should_normalize_separators() {
+        let (dir, source) = create_project(&[
+            ("index.html", "content-['index.html']"),
+            ("src/nested/a.html", "content-['src/nested/a.html']"),
+        ]);
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![source],
+            normalize_separators: true,
+            ..Default::default()
+        });
+
+        scanner.scan();
+
+        let files = scanner.get_files();
+        assert!(!files.is_empty());
+        assert!(files.iter().all(|file| !file.contains('\\')));
+
+        let globs = scanner.get_globs();
+        assert!(!globs.is_empty());
+        assert!(globs.iter().all(|glob| !glob.base.contains('\\')));
+
+        let dirs = scanner.get_dirs();
+        assert!(!dirs.is_empty());
+        assert!(dirs.iter().all(|dir| !dir.contains('\\')));
+    }
+
+    #[test]
+    fn it_should_use_a_remapped_pre_processor() {
+        let content =
+            ChangedContent::Content("div.flex.items-center.px-2.5".to_owned(), "inc".into());
+
+        // Without a pre-processor, the classes are stuck together
+        let mut scanner = Scanner::new(None);
+        assert_eq!(scanner.pre_processors().get("inc"), None);
+        let candidates = scanner.scan_content(vec![content.clone()]);
+        assert!(!candidates.contains(&"items-center".to_owned()));
+
+        // Route `.inc` files through the Pug pre-processor
+        let mut scanner = Scanner::new(None);
+        scanner
+            .pre_processors_mut()
+            .insert("inc".to_owned(), PreProcessorKind::Pug);
+        let candidates = scanner.scan_content(vec![content]);
+        assert_eq!(candidates, vec!["div", "flex", "items-center", "px-2.5"]);
+    }
+
+    #[test]
+    fn it_should_only_scan_json_strings_under_the_configured_key_paths() {
+        let content = ChangedContent::Content(
+            r#"{"title": "underline", "components": {"button": {"className": "px-4 font-bold"}}}"#
+                .to_owned(),
+            "json".into(),
+        );
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            json_key_paths: Some(vec!["components.*.className".to_owned()]),
+            ..Default::default()
+        });
+        let candidates = scanner.scan_content(vec![content.clone()]);
+        assert_eq!(candidates, vec!["font-bold", "px-4"]);
+
+        // Without key paths, all strings are scanned
+        let mut scanner = Scanner::new(None);
+        let candidates = scanner.scan_content(vec![content]);
+        assert!(candidates.contains(&"underline".to_owned()));
+    }
+
+    #[test]
+    fn it_should_pre_process_typescript_and_modules_as_jsx() {
+        for extension in ["cjs", "mjs", "ts"] {
+            assert_eq!(
+                PreProcessorKind::for_extension(extension),
+                Some(PreProcessorKind::Jsx)
+            );
+
+            let mut scanner = Scanner::new(None);
+            let candidates = scanner.scan_content(vec![ChangedContent::Content(
+                "const base = css`@apply flex-col;`".to_owned(),
+                extension.into(),
+            )]);
+
+            assert!(candidates.contains(&"flex-col".to_owned()), "{extension}");
+        }
+    }
+
+    #[test]
+    fn it_should_pre_process_content_with_a_default_scanner() {
+        let mut scanner = Scanner::default();
+        let candidates = scanner.scan_content(vec![ChangedContent::Content(
+            "div.flex.p-4 hello".to_owned(),
+            "pug".into(),
+        )]);
+
+        assert_eq!(candidates, vec!["div", "flex", "hello", "p-4"]);
+    }
+
+    #[test]
+    fn it_should_exclude_candidates_from_a_blocklist_file() {
+        let (dir, _) = create_project(&[
+            (
+                "index.html",
+                "<div class='flex underline generated-xyz'></div>",
+            ),
+            (".tw-blocklist", "generated-xyz\n\n  underline  \n"),
+        ]);
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![GlobEntry {
+                base: dir.to_string_lossy().to_string(),
+                pattern: "**/*.html".to_owned(),
+            }],
+            blocklist_file: Some(dir.join(".tw-blocklist")),
+            ..Default::default()
+        });
+
+        let candidates = scanner.scan();
+        assert_eq!(candidates, vec!["class", "flex"]);
+
+        let candidates = scanner.scan_content(vec![ChangedContent::Content(
+            "<div class='generated-xyz italic'></div>".to_owned(),
+            "html".into(),
+        )]);
+        assert_eq!(candidates, vec!["italic"]);
+    }
+
+    #[test]
+    fn it_should_detect_changes_within_the_same_mtime() {
+        let (dir, source) = create_project(&[("index.html", "content-['aaa']")]);
+
+        // Force the exact same modification time for every write
+        let mtime = std::time::SystemTime::now();
+        let write = |content: &str| {
+            fs::write(dir.join("index.html"), content).unwrap();
+            fs::File::options()
+                .write(true)
+                .open(dir.join("index.html"))
+                .unwrap()
+                .set_modified(mtime)
+                .unwrap();
+        };
+
+        write("content-['aaa']");
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![source],
+            content_hash: true,
+            ..Default::default()
+        });
+
+        assert_eq!(scanner.scan(), vec!["content-['aaa']"]);
+
+        // Different size, same modification time
+        write("content-['bbbb']");
+        assert_eq!(scanner.scan(), vec!["content-['aaa']", "content-['bbbb']"]);
+
+        // Same size, same modification time
+        write("content-['cccc']");
+        assert_eq!(
+            scanner.scan(),
+            vec!["content-['aaa']", "content-['bbbb']", "content-['cccc']"]
+        );
+    }
+
+    #[test]
+    fn it_should_list_the_globs_for_a_single_source() {
+        let (dir, _) = create_project(&[
+            ("project-a/index.html", "content-['project-a/index.html']"),
+            ("project-a/src/a.jsx", "content-['project-a/src/a.jsx']"),
+            ("project-b/index.html", "content-['project-b/index.html']"),
+        ]);
+
+        let base = dunce::canonicalize(&dir).unwrap();
+        let project_a = GlobEntry {
+            base: base.join("project-a").to_string_lossy().to_string(),
+            pattern: "**/*".to_owned(),
+        };
+        let project_b = GlobEntry {
+            base: base.join("project-b").to_string_lossy().to_string(),
+            pattern: "*.html".to_owned(),
+        };
+
+        let scanner = Scanner::new(Some(vec![project_a.clone(), project_b.clone()]));
+
+        let globs = scanner.globs_for_source(&project_a);
+        assert_eq!(globs.len(), 2);
+        assert_eq!(
+            globs[0],
+            GlobEntry {
+                base: project_a.base.clone(),
+                pattern: "*".to_owned(),
+            }
+        );
+        assert_eq!(
+            path::PathBuf::from(&globs[1].base),
+            base.join("project-a").join("src")
+        );
+        assert!(globs[1].pattern.starts_with("**/*.{"));
+        assert!(globs[1].pattern.contains("jsx"));
+
+        assert_eq!(scanner.globs_for_source(&project_b), vec![project_b]);
+    }
+
+    #[test]
+    #[cfg(feature = "compression")]
+    fn it_should_scan_brotli_compressed_files() {
+        use std::io::Write;
+
+        let (dir, source) = create_project(&[("index.html", "<div class='flex'></div>")]);
+
+        let mut compressed = vec![];
+        {
+            let mut writer = brotli::CompressorWriter::new(&mut compressed, 4096, 5, 22);
+            writer
+                .write_all(b"<div class='underline' data-x='.mt-4'></div>")
+                .unwrap();
+        }
+        fs::write(dir.join("cached.pug.br"), compressed).unwrap();
+
+        let mut scanner = Scanner::new(Some(vec![source]));
+
+        let candidates = scanner.scan();
+
+        // The decompressed content is pre-processed based on the inner extension
+        assert!(candidates.contains(&"underline".to_owned()));
+        assert!(candidates.contains(&"mt-4".to_owned()));
+        assert!(candidates.contains(&"flex".to_owned()));
+    }
+
+    #[test]
+    fn it_should_list_the_candidates_since_a_checkpoint() {
+        let (_, source) = create_project(&[("index.html", "<div class='flex underline'></div>")]);
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![source],
+            checkpoints: true,
+            ..Default::default()
+        });
+
+        let start = scanner.checkpoint();
+        scanner.scan();
+
+        let a = scanner.checkpoint();
+        assert!(scanner.candidates_since(a).is_empty());
+
+        scanner.scan_content(vec![ChangedContent::Content(
+            "<div class='flex italic'></div>".to_owned(),
+            "html".into(),
+        )]);
+
+        let b = scanner.checkpoint();
+
+        scanner.scan_content(vec![ChangedContent::Content(
+            "<div class='font-bold'></div>".to_owned(),
+            "html".into(),
+        )]);
+
+        assert_eq!(scanner.candidates_since(a), vec!["font-bold", "italic"]);
+        assert_eq!(scanner.candidates_since(b), vec!["font-bold"]);
+        assert_eq!(
+            scanner.candidates_since(start),
+            vec!["class", "flex", "font-bold", "italic", "underline"]
+        );
+    }
+
+    #[test]
+    fn it_should_group_candidates_by_extension() {
+        let (_, source) = create_project(&[
+            ("index.html", "<div class='flex underline'></div>"),
+            ("src/a.tsx", "<div className='flex italic'></div>"),
+            ("src/b.tsx", "<div className='font-bold'></div>"),
+            ("views/c.blade.php", "<div class='p-4'></div>"),
+        ]);
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![source.clone()],
+            provenance: true,
+            ..Default::default()
+        });
+
+        let groups = scanner.scan_by_extension().unwrap();
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups["html"], vec!["class", "flex", "underline"]);
+        assert_eq!(
+            groups["tsx"],
+            vec!["className", "flex", "font-bold", "italic"]
+        );
+        assert_eq!(groups["blade.php"], vec!["class", "p-4"]);
+
+        // Without provenance, the files the candidates came from are unknown
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![source],
+            ..Default::default()
+        });
+
+        assert_eq!(scanner.scan_by_extension(), None);
+    }
+
+    #[test]
+    fn it_should_stop_when_exceeding_the_file_limit() {
+        let (_, source) = create_project(&[
+            ("a.html", "content-['a.html']"),
+            ("b.html", "content-['b.html']"),
+            ("src/c.html", "content-['src/c.html']"),
+        ]);
+
+        let scanner_with_limit = |max_files| {
+            Scanner::with_options(ScanOptions {
+                sources: vec![source.clone()],
+                max_files: Some(max_files),
+                ..Default::default()
+            })
+        };
+
+        let mut scanner = scanner_with_limit(2);
+        assert!(scanner.scan().is_empty());
+        assert!(scanner.get_files().is_empty());
+        assert!(scanner.file_limit_exceeded());
+
+        let mut scanner = scanner_with_limit(3);
+        assert_eq!(scanner.scan().len(), 3);
+        assert!(!scanner.file_limit_exceeded());
+    }
+
+    #[test]
+    fn it_should_stop_when_new_files_exceed_the_file_limit() {
+        let (dir, source) = create_project(&[
+            ("a.html", "content-['a.html']"),
+            ("b.html", "content-['b.html']"),
+        ]);
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![source],
+            max_files: Some(3),
+            verbose: true,
+            ..Default::default()
+        });
+
+        assert_eq!(scanner.scan().len(), 2);
+        assert!(!scanner.file_limit_exceeded());
+
+        // Still within the limit
+        create_files_in(&dir, &[("c.html", "content-['c.html']")]);
+        assert_eq!(scanner.scan().len(), 3);
+        assert!(!scanner.file_limit_exceeded());
+
+        create_files_in(&dir, &[("src/d.html", "content-['src/d.html']")]);
+        scanner.scan();
+        assert!(scanner.get_files().is_empty());
+        assert!(scanner.file_limit_exceeded());
+        assert!(scanner
+            .last_scan_log()
+            .contains(&ScanEvent::FileLimitExceeded(3)));
+    }
+
+    #[test]
+    fn it_should_filter_noise_when_enabled() {
+        let content = ChangedContent::Content(
+            r#"
+                import { useState } from 'react'
+                export function Button(props) {
+                  const [open, setOpen] = useState(false)
+                  return <button onClick={() => setOpen(!open)} className="flex px-4 hover:underline" />
+                }
+            "#
+            .to_owned(),
+            "tsx".into(),
+        );
+
+        let candidates = Scanner::new(None).scan_content(vec![content.clone()]);
+        assert!(candidates.contains(&"useState".to_owned()));
+
+        let candidates = Scanner::with_options(ScanOptions {
+            noise_filter: Some(noise_filter::NoiseFilter::Loose),
+            ..Default::default()
+        })
+        .scan_content(vec![content]);
+
+        assert_eq!(candidates, vec!["flex", "hover:underline", "px-4"]);
+    }
+
+    #[test]
+    fn it_should_scan_extensionless_files_matching_a_content_signature() {
+        let (_, source) = create_project(&[
+            ("index.html", "content-['index.html']"),
+            (
+                "pages/about",
+                "\n<!doctype html>\n<div class=\"content-['pages/about']\"></div>",
+            ),
+            ("bin/build", "#!/bin/sh\necho content-['bin/build']"),
+        ]);
+
+        let scanner_with_signatures = |content_signatures: Vec<String>| {
+            Scanner::with_options(ScanOptions {
+                sources: vec![source.clone()],
+                content_signatures,
+                ..Default::default()
+            })
+        };
+
+        let mut scanner = scanner_with_signatures(vec![]);
+        assert_eq!(scanner.scan(), vec!["content-['index.html']"]);
+
+        let mut scanner = scanner_with_signatures(vec!["<!DOCTYPE html>".to_owned()]);
+        assert_eq!(
+            scanner.scan(),
+            vec!["class", "content-['index.html']", "content-['pages/about']"]
+        );
+    }
+
+    #[test]
+    fn it_should_scan_candidates_files_and_globs_at_once() {
+        let (dir, source) = create_project(&[
+            ("index.html", "content-['index.html']"),
+            ("src/a.html", "content-['src/a.html']"),
+            ("src/nested/b.jsx", "content-['src/nested/b.jsx']"),
+            ("styles/c.styl", "content-['styles/c.styl']"),
+        ]);
+
+        let sources = vec![
+            source,
+            GlobEntry {
+                base: dir.to_string_lossy().to_string(),
+                pattern: "styles/*.styl".to_owned(),
+            },
+        ];
+
+        let mut scanner = Scanner::new(Some(sources.clone()));
+        let candidates = scanner.scan();
+        let mut files = scanner.get_files();
+        let globs = scanner.get_globs();
+
+        let result = Scanner::new(Some(sources)).scan_all();
+
+        let mut result_files = result.files.clone();
+        result_files.sort();
+        files.sort();
+
+        assert_eq!(result.candidates, candidates);
+        assert_eq!(result_files, files);
+        assert_eq!(result.globs, globs);
+        assert_eq!(result.candidates.len(), 4);
+    }
+
+    #[test]
+    fn it_should_scan_many_projects_independently() {
+        let (dir, _) = create_project(&[
+            ("packages/a/index.html", "content-['packages/a/index.html']"),
+            ("packages/a/src/a.jsx", "content-['packages/a/src/a.jsx']"),
+            ("packages/b/index.html", "content-['packages/b/index.html']"),
+        ]);
+
+        let project = |name: &str| {
+            vec![GlobEntry {
+                base: dir
+                    .join("packages")
+                    .join(name)
+                    .to_string_lossy()
+                    .to_string(),
+                pattern: "**/*".to_owned(),
+            }]
+        };
+
+        let results = scan_many(vec![project("a"), project("b")]);
+
+        assert_eq!(
+            results,
+            vec![
+                vec![
+                    "content-['packages/a/index.html']",
+                    "content-['packages/a/src/a.jsx']",
+                ],
+                vec!["content-['packages/b/index.html']"],
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_produce_identical_results_in_deterministic_mode() {
+        let (dir, source) = create_project(&[
+            ("index.html", "content-['index.html']"),
+            ("src/b.html", "content-['src/b.html']"),
+            ("src/a.html", "content-['src/a.html']"),
+            ("src/nested/c.jsx", "content-['src/nested/c.jsx']"),
+            ("lib/d.tsx", "content-['lib/d.tsx']"),
+            ("styles/e.styl", "content-['styles/e.styl']"),
+        ]);
+
+        let scan_deterministic = || {
+            Scanner::with_options(ScanOptions {
+                sources: vec![
+                    source.clone(),
+                    GlobEntry {
+                        base: dir.to_string_lossy().to_string(),
+                        pattern: "styles/*.styl".to_owned(),
+                    },
+                ],
+                deterministic: true,
+                ..Default::default()
+            })
+            .scan_all()
+        };
+
+        let a = scan_deterministic();
+        let b = scan_deterministic();
+
+        assert_eq!(a.candidates, b.candidates);
+        assert_eq!(a.files, b.files);
+        assert_eq!(a.globs, b.globs);
+
+        let mut sorted_files = a.files.clone();
+        sorted_files.sort();
+        assert_eq!(a.files, sorted_files);
+        assert_eq!(a.files.len(), 6);
+    }
+
+    #[test]
+    fn it_should_remove_the_candidates_of_a_removed_source() {
+        let (dir, _) = create_project(&[
+            ("packages/a/index.html", "flex content-['packages/a']"),
+            ("packages/b/index.html", "flex content-['packages/b']"),
+        ]);
+
+        let source = |name: &str| GlobEntry {
+            base: dir
+                .join("packages")
+                .join(name)
+                .to_string_lossy()
+                .to_string(),
+            pattern: "**/*".to_owned(),
+        };
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![source("a"), source("b")],
+            provenance: true,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            scanner.scan(),
+            vec!["content-['packages/a']", "content-['packages/b']", "flex"]
+        );
+
+        scanner.remove_source(&source("a"));
+
+        assert_eq!(scanner.scan(), vec!["content-['packages/b']", "flex"]);
+        assert_eq!(scanner.get_files().len(), 1);
+    }
+
+    #[test]
+    fn it_should_know_whether_files_changed_since_the_last_scan() {
+        let (dir, source) = create_project(&[("index.html", "content-['index.html']")]);
+
+        let mut scanner = Scanner::new(Some(vec![source]));
+
+        // Nothing was scanned yet
+        assert!(scanner.has_changes());
+
+        scanner.scan();
+        assert!(!scanner.has_changes());
+
+        // Modify a file
+        create_files_in(&dir, &[("index.html", "content-['index.html'] flex")]);
+        assert!(scanner.has_changes());
+
+        scanner.scan();
+        assert!(!scanner.has_changes());
+
+        // We have to sleep because it might run too fast (seriously) and the
+        // mtimes of the directories end up being the same as the last time we
+        // checked them
+        sleep(Duration::from_millis(100));
+
+        // Create a file
+        create_files_in(&dir, &[("new.html", "content-['new.html']")]);
+        assert!(scanner.has_changes());
+    }
+
+    #[test]
+    fn it_should_keep_a_log_of_the_last_scan() {
+        let (dir, source) = create_project(&[
+            ("index.html", "content-['index.html']"),
+            ("src/a.html", "content-['src/a.html']"),
+        ]);
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![source.clone()],
+            verbose: true,
+            ..Default::default()
+        });
+
+        scanner.scan();
+
+        let log = scanner.last_scan_log();
+        assert_eq!(log[0], ScanEvent::Root(dunce::canonicalize(&dir).unwrap()));
+        assert!(matches!(
+            log[1],
+            ScanEvent::SourcesResolved { files: 2, .. }
+        ));
+        assert!(log.contains(&ScanEvent::FilesRead(2)));
+        assert!(log.contains(&ScanEvent::NewCandidates(2)));
+
+        // The log only contains the last scan
+        scanner.scan();
+
+        let log = scanner.last_scan_log();
+        assert!(log.contains(&ScanEvent::NewFiles(0)));
+        assert!(log.contains(&ScanEvent::FilesRead(0)));
+        assert!(log.contains(&ScanEvent::NewCandidates(0)));
+        assert!(!log.iter().any(|x| matches!(x, ScanEvent::Root(_))));
+
+        // Nothing is logged without the `verbose` option
+        let mut scanner = Scanner::new(Some(vec![source]));
+        scanner.scan();
+        assert!(scanner.last_scan_log().is_empty());
+    }
+
+    #[test]
+    fn it_should_never_scan_generated_outputs() {
+        let (dir, source) = create_project(&[
+            ("index.html", "content-['index.html']"),
+            ("safelist.html", "content-['safelist.html']"),
+        ]);
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![
+                source,
+                GlobEntry {
+                    base: dir.to_string_lossy().to_string(),
+                    pattern: "*.html".to_owned(),
+                },
+            ],
+            generated_outputs: vec![dir.join("safelist.html"), dir.join("out/safelist.html")],
+            ..Default::default()
+        });
+
+        assert_eq!(scanner.scan(), vec!["content-['index.html']"]);
+        assert!(!scanner
+            .get_files()
+            .iter()
+            .any(|path| path.ends_with("safelist.html")));
+
+        // Generated outputs that are created later on are ignored as well
+        sleep(Duration::from_millis(100));
+        create_files_in(
+            &dir,
+            &[("out/safelist.html", "content-['out/safelist.html']")],
+        );
+
+        assert_eq!(scanner.scan(), vec!["content-['index.html']"]);
+    }
+
+    #[test]
+    fn it_should_scan_ignored_files_without_default_ignores() {
+        let (_, source) = create_project(&[
+            ("index.html", "content-['index.html']"),
+            ("yarn.lock", "content-['yarn.lock']"),
+            ("styles.css", "content-['styles.css']"),
+        ]);
+
+        let scanner_with_default_ignores = |no_default_ignores: bool| {
+            Scanner::with_options(ScanOptions {
+                sources: vec![source.clone()],
+                no_default_ignores,
+                ..Default::default()
+            })
+        };
+
+        let mut scanner = scanner_with_default_ignores(false);
+        assert_eq!(scanner.scan(), vec!["content-['index.html']"]);
+
+        let mut scanner = scanner_with_default_ignores(true);
+        assert_eq!(
+            scanner.scan(),
+            vec![
+                "content-['index.html']",
+                "content-['styles.css']",
+                "content-['yarn.lock']",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_use_the_default_extension_for_extension_less_files() {
+        let (dir, _) = create_project(&[("includes/header", "nav.flex.px-4")]);
+
+        let scanner_with_default_extension = |default_extension: Option<&str>| {
+            Scanner::with_options(ScanOptions {
+                sources: vec![GlobEntry {
+                    base: dir.to_string_lossy().to_string(),
+                    pattern: "includes/*".to_owned(),
+                }],
+                default_extension: default_extension.map(|x| x.to_owned()),
+                ..Default::default()
+            })
+        };
+
+        let mut scanner = scanner_with_default_extension(None);
+        assert_eq!(scanner.scan(), vec!["px-4"]);
+
+        let mut scanner = scanner_with_default_extension(Some("pug"));
+        assert_eq!(scanner.scan(), vec!["flex", "nav", "px-4"]);
+    }
+
+    #[test]
+    fn it_should_use_the_default_extension_when_scanning_for_css_variables() {
+        let (dir, _) = create_project(&[("tokens/colors", "--internal=var(--brand-color)")]);
+
+        let scanner_with_default_extension = |default_extension: Option<&str>| {
+            let mut scanner = Scanner::with_options(ScanOptions {
+                sources: vec![GlobEntry {
+                    base: dir.to_string_lossy().to_string(),
+                    pattern: "tokens/*".to_owned(),
+                }],
+                default_extension: default_extension.map(|x| x.to_owned()),
+                ..Default::default()
+            });
+            scanner
+                .pre_processors_mut()
+                .insert("properties".to_owned(), PreProcessorKind::Properties);
+            scanner
+        };
+
+        let mut scanner = scanner_with_default_extension(None);
+        assert_eq!(
+            scanner.scan_variables(),
+            vec!["--brand-color", "--internal"]
+        );
+
+        // Only the values are scanned in `.properties` files
+        let mut scanner = scanner_with_default_extension(Some("properties"));
+        assert_eq!(scanner.scan_variables(), vec!["--brand-color"]);
+    }
+
+    #[test]
+    fn it_should_report_invalid_sources() {
+        let dir = tempdir().unwrap().into_path();
+        create_files_in(&dir, &[("index.html", "content-['index.html']")]);
+
+        let source = |base: &path::Path, pattern: &str| GlobEntry {
+            base: base.to_string_lossy().to_string(),
+            pattern: pattern.to_owned(),
+        };
+
+        // Malformed pattern
+        let result = Scanner::try_new(Some(vec![source(&dir, "src/**/*.{html,js")]));
+        assert!(
+            matches!(result, Err(ScannerError::InvalidPattern(pattern)) if pattern == "src/**/*.{html,js")
+        );
+
+        // Missing base
+        let result = Scanner::try_new(Some(vec![source(&dir.join("missing"), "**/*")]));
+        assert!(matches!(result, Err(ScannerError::MissingBase(_))));
+
+        // Missing blocklist
+        let result = Scanner::try_with_options(ScanOptions {
+            sources: vec![source(&dir, "**/*")],
+            blocklist_file: Some(dir.join("blocklist.txt")),
+            ..Default::default()
+        });
+        assert!(matches!(result, Err(ScannerError::Blocklist(_))));
+
+        // Valid sources
+        let mut scanner = Scanner::try_new(Some(vec![source(&dir, "**/*.{html,js}")])).unwrap();
+        assert_eq!(scanner.scan(), vec!["content-['index.html']"]);
+    }
+
+    #[test]
+    fn it_should_not_look_for_ignore_files_outside_of_the_project_root() {
+        // An outer repository that happens to contain our project
+        let (dir, _) = create_project(&[
+            (".gitignore", "generated.html"),
+            ("projects/app/.gitignore", "ignored.html"),
+            ("projects/app/index.html", "content-['index.html']"),
+            ("projects/app/generated.html", "content-['generated.html']"),
+            ("projects/app/ignored.html", "content-['ignored.html']"),
+            ("projects/app/src/.gitignore", "nested.html"),
+            (
+                "projects/app/src/nested.html",
+                "content-['src/nested.html']",
+            ),
+        ]);
+
+        // A nested repository inside of our project
+        let _ = Command::new("git")
+            .arg("init")
+            .current_dir(dir.join("projects/app/src"))
+            .output();
+
+        let scanner_with_project_root = |project_root: Option<path::PathBuf>| {
+            Scanner::with_options(ScanOptions {
+                sources: vec![GlobEntry {
+                    base: dir.join("projects/app").to_string_lossy().to_string(),
+                    pattern: "**/*".to_owned(),
+                }],
+                project_root,
+                ..Default::default()
+            })
+        };
+
+        // The `.gitignore` of the outer repository is used
+        let mut scanner = scanner_with_project_root(None);
+        assert_eq!(scanner.scan(), vec!["content-['index.html']"]);
+
+        // Only the `.gitignore` files inside of the project root are used
+        let mut scanner = scanner_with_project_root(Some(dir.join("projects/app")));
+        assert_eq!(
+            scanner.scan(),
+            vec!["content-['generated.html']", "content-['index.html']"]
+        );
+    }
+
+    #[test]
+    fn it_should_root_ignore_files_in_parent_directories_at_their_own_directory() {
+        let (dir, _) = create_project(&[
+            (".gitignore", "/index.html"),
+            ("apps/.gitignore", "/web/dist\nweb/build"),
+            ("apps/web/index.html", "content-['index.html']"),
+            ("apps/web/dist/index.html", "content-['dist/index.html']"),
+            ("apps/web/build/index.html", "content-['build/index.html']"),
+            (
+                "apps/web/src/dist/index.html",
+                "content-['src/dist/index.html']",
+            ),
+        ]);
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![GlobEntry {
+                base: dir.join("apps/web").to_string_lossy().to_string(),
+                pattern: "**/*".to_owned(),
+            }],
+            project_root: Some(dir.clone()),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            scanner.scan(),
+            vec!["content-['index.html']", "content-['src/dist/index.html']"]
+        );
+    }
+
+    #[test]
+    fn it_should_honor_npmignore_files_when_enabled() {
+        let dir = tempdir().unwrap().into_path();
+
+        create_files_in(
+            &dir,
+            &[
+                ("node_modules/my-ui/.npmignore", "src/\n*.test.js"),
+                ("node_modules/my-ui/.gitignore", "tmp/"),
+                (
+                    "node_modules/my-ui/dist/index.js",
+                    "content-['dist/index.js']",
+                ),
+                (
+                    "node_modules/my-ui/dist/index.test.js",
+                    "content-['dist/index.test.js']",
+                ),
+                (
+                    "node_modules/my-ui/src/index.ts",
+                    "content-['src/index.ts']",
+                ),
+                (
+                    "node_modules/my-ui/tmp/index.js",
+                    "content-['tmp/index.js']",
+                ),
+            ],
+        );
+
+        let scanner_with_npmignore = |npmignore: bool| {
+            Scanner::with_options(ScanOptions {
+                sources: vec![GlobEntry {
+                    base: dir.join("node_modules/my-ui").to_string_lossy().to_string(),
+                    pattern: "**/*".to_owned(),
+                }],
+                npmignore,
+                ..Default::default()
+            })
+        };
+
+        let mut scanner = scanner_with_npmignore(false);
+        assert_eq!(
+            scanner.scan(),
+            vec![
+                "content-['dist/index.js']",
+                "content-['dist/index.test.js']",
+                "content-['src/index.ts']",
+            ]
+        );
+
+        let mut scanner = scanner_with_npmignore(true);
+        assert_eq!(scanner.scan(), vec!["content-['dist/index.js']"]);
+    }
+
+    #[test]
+    fn it_should_scan_flat_projects_with_ignored_folders() {
+        let (globs, candidates) = scan(&[
+            (".gitignore", "node_modules\npublic"),
+            ("index.html", "content-['index.html']"),
+            ("about.html", "content-['about.html']"),
+            ("node_modules/pkg/index.js", "content-['node_modules']"),
+            ("public/index.html", "content-['public']"),
+        ]);
+
+        assert_eq!(globs, vec!["*", "about.html", "index.html"]);
+        assert_eq!(
+            candidates,
+            vec!["content-['about.html']", "content-['index.html']"]
+        );
+    }
+
+    #[test]
+    fn it_should_list_the_files_matching_a_glob() {
+        let (dir, _) = create_project(&[
+            ("index.html", ""),
+            ("src/app.tsx", ""),
+            ("src/app.ts", ""),
+            ("src/components/button.tsx", ""),
+            ("styles/main.styl", ""),
+        ]);
+
+        let base = dir.to_string_lossy().to_string();
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![
+                GlobEntry {
+                    base: base.clone(),
+                    pattern: "**/*".to_owned(),
+                },
+                GlobEntry {
+                    base: base.clone(),
+                    pattern: "styles/*.styl".to_owned(),
+                },
+            ],
+            normalize_separators: true,
+            ..Default::default()
+        });
+
+        let root = format!("{}/", dunce::canonicalize(&dir).unwrap().display()).replace('\\', "/");
+        let relative = |files: Vec<String>| -> Vec<String> {
+            files.iter().map(|x| x.replace(&root, "")).collect()
+        };
+
+        assert_eq!(
+            relative(scanner.files_matching("**/*.tsx")),
+            vec!["src/app.tsx", "src/components/button.tsx"]
+        );
+        assert_eq!(
+            relative(scanner.files_matching("src/*.{ts,tsx}")),
+            vec!["src/app.ts", "src/app.tsx"]
+        );
+        assert_eq!(
+            relative(scanner.files_matching("**/*.styl")),
+            vec!["styles/main.styl"]
+        );
+        assert!(scanner.files_matching("**/*.vue").is_empty());
+    }
+
+    #[test]
+    fn it_should_list_the_files_matching_a_glob_relative_to_the_base() {
+        let (dir, _) = create_project(&[
+            ("project-a/src/app.tsx", ""),
+            ("project-b/src/app.tsx", ""),
+            ("project-b/src/components/button.tsx", ""),
+        ]);
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            base: Some(dir.join("project-a").to_string_lossy().to_string()),
+            sources: vec![GlobEntry {
+                base: "src".to_owned(),
+                pattern: "**/*".to_owned(),
+            }],
+            normalize_separators: true,
+            ..Default::default()
+        });
+
+        let root = format!("{}/", dunce::canonicalize(&dir).unwrap().display()).replace('\\', "/");
+        let relative = |files: Vec<String>| -> Vec<String> {
+            files.iter().map(|x| x.replace(&root, "")).collect()
+        };
+
+        assert_eq!(
+            relative(scanner.files_matching("*.tsx")),
+            vec!["project-a/src/app.tsx"]
+        );
+
+        scanner.set_base(dir.join("project-b"));
+
+        assert_eq!(
+            relative(scanner.files_matching("**/*.tsx")),
+            vec![
+                "project-b/src/app.tsx",
+                "project-b/src/components/button.tsx"
+            ]
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn it_should_scan_on_the_blocking_thread_pool() {
+        let (_, source) = create_project(&[
+            ("index.html", "content-['index.html']"),
+            ("src/a.html", "content-['src/a.html']"),
+        ]);
+
+        let sources = vec![source];
+
+        let expected = Scanner::new(Some(sources.clone())).scan();
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        let mut scanner = Scanner::new(Some(sources));
+        let candidates = runtime.block_on(scanner.scan_async()).unwrap();
+
+        assert_eq!(candidates, expected);
+
+        // The scanner keeps its state
+        assert_eq!(scanner.get_files().len(), 2);
+    }
+
+    #[test]
+    fn it_should_only_scan_files_allowed_by_the_path_filter() {
+        let (_, source) = create_project(&[
+            ("pages/home.html", "content-['pages/home.html']"),
+            ("pages/home.meta", "active"),
+            ("pages/about.html", "content-['pages/about.html']"),
+            ("pages/about.meta", "inactive"),
+            ("pages/contact.html", "content-['pages/contact.html']"),
+            ("drafts/new.html", "content-['drafts/new.html']"),
+            ("drafts/new.meta", "active"),
+        ]);
+
+        // Only include pages that are marked as active in their sibling `.meta` file
+        let path_filter = PathFilter::new(|path| {
+            if path.is_dir() {
+                return !path.ends_with("drafts");
+            }
+
+            path.extension().is_some_and(|ext| ext == "html")
+                && fs::read_to_string(path.with_extension("meta"))
+                    .is_ok_and(|meta| meta.trim() == "active")
+        });
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![source],
+            path_filter: Some(path_filter),
+            ..Default::default()
+        });
+
+        assert_eq!(scanner.scan(), vec!["content-['pages/home.html']"]);
+    }
+
+    #[test]
+    fn it_should_return_the_new_candidates_across_all_batches() {
+        let mut scanner = Scanner::new(None);
+        scanner.scan_content(vec![ChangedContent::Content(
+            "<div class=\"flex\"></div>".into(),
+            "html".into(),
+        )]);
+
+        let candidates = scanner.scan_content_batched(vec![
+            vec![ChangedContent::Content(
+                "<div class=\"flex underline px-4\"></div>".into(),
+                "html".into(),
+            )],
+            vec![
+                ChangedContent::Content("<div class=\"px-4 italic\"></div>".into(), "html".into()),
+                ChangedContent::Content("<div class=\"bg-red-500\"></div>".into(), "html".into()),
+            ],
+        ]);
+
+        // `flex` and `div` were already known, `px-4` is only reported once
+        assert_eq!(
+            candidates,
+            vec!["bg-red-500", "italic", "px-4", "underline"]
+        );
+    }
+
+    #[test]
+    fn it_should_skip_declaration_files_and_source_maps_by_default() {
+        let (_, source) = create_project(&[
+            ("src/index.ts", "content-['src/index.ts']"),
+            ("src/index.d.ts", "content-['src/index.d.ts']"),
+            ("src/types.d.mts", "content-['src/types.d.mts']"),
+            ("dist/app.js", "content-['dist/app.js']"),
+            ("dist/app.js.map", "content-['dist/app.js.map']"),
+        ]);
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![source.clone()],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            scanner.scan(),
+            vec!["content-['dist/app.js']", "content-['src/index.ts']"]
+        );
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![source],
+            include_declaration_files: true,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            scanner.scan(),
+            vec![
+                "content-['dist/app.js']",
+                "content-['dist/app.js.map']",
+                "content-['src/index.d.ts']",
+                "content-['src/index.ts']",
+                "content-['src/types.d.mts']",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_count_the_files_contributing_each_candidate() {
+        let (_, source) = create_project(&[
+            ("index.html", "<div class='flex underline'></div>"),
+            ("src/a.html", "<div class='flex italic'></div>"),
+            ("src/b.html", "<div class='flex italic underline'></div>"),
+            ("src/c.html", "<div class='font-bold'></div>"),
+        ]);
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![source.clone()],
+            provenance: true,
+            ..Default::default()
+        });
+
+        scanner.scan();
+
+        assert_eq!(
+            scanner.candidate_frequency(),
+            Some(vec![
+                ("class".to_string(), 4),
+                ("flex".to_string(), 3),
+                ("font-bold".to_string(), 1),
+                ("italic".to_string(), 2),
+                ("underline".to_string(), 2),
+            ])
+        );
+
+        // Without provenance, nothing is tracked
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![source],
+            ..Default::default()
+        });
+
+        scanner.scan();
+
+        assert_eq!(scanner.candidate_frequency(), None);
+    }
+
+    #[test]
+    fn it_should_resolve_relative_sources_against_a_new_base() {
+        let (dir, _) = create_project(&[
+            (
+                "project-a/src/index.html",
+                "content-['project-a/src/index.html']",
+            ),
+            (
+                "project-b/src/index.html",
+                "content-['project-b/src/index.html']",
+            ),
+        ]);
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            base: Some(dir.join("project-a").to_string_lossy().to_string()),
+            sources: vec![GlobEntry {
+                base: "src".to_owned(),
+                pattern: "**/*".to_owned(),
+            }],
+            ..Default::default()
+        });
+
+        assert_eq!(scanner.scan(), vec!["content-['project-a/src/index.html']"]);
+
+        scanner.set_base(dir.join("project-b"));
+
+        assert_eq!(scanner.scan(), vec!["content-['project-b/src/index.html']"]);
+        assert!(scanner
+            .get_files()
+            .iter()
+            .all(|file| file.contains("project-b")));
+    }
+
+    #[test]
+    fn it_should_track_the_segments_of_concatenated_content() {
+        let mut scanner = Scanner::with_options(ScanOptions {
+            provenance: true,
+            ..Default::default()
+        });
+
+        let manifest = [
+            "@@ src/header.html",
+            "<div class=\"flex underline\"></div>",
+            "@@ src/footer.html",
+            "<div class=\"flex italic\"></div>",
+        ]
+        .join("\n");
+
+        let candidates = scanner.scan_content(vec![ChangedContent::Concatenated(
+            manifest,
+            "@@".into(),
+            "html".into(),
+        )]);
+
+        assert_eq!(candidates, vec!["class", "flex", "italic", "underline"]);
+
+        let sources_for = |candidate: &str| -> Vec<String> {
+            scanner
+                .source_for_candidate(candidate)
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect()
+        };
+
+        assert_eq!(
+            sources_for("flex"),
+            vec!["src/footer.html", "src/header.html"]
+        );
+    }
+
+    #[test]
+    fn it_should_only_return_candidates_missing_from_the_baseline() {
+        let (_, source) = create_project(&[
+            ("index.html", "<div class='flex underline'></div>"),
+            ("src/a.html", "<div class='italic font-bold'></div>"),
+        ]);
+
+        let mut scanner = Scanner::new(Some(vec![source]));
+
+        let baseline =
+            FxHashSet::from_iter(["class".to_owned(), "flex".to_owned(), "italic".to_owned()]);
+
+        assert_eq!(
+            scanner.scan_against_baseline(&baseline),
+            vec!["font-bold", "underline"]
+        );
+
+        // The baseline doesn't influence the candidates tracked by the scanner
+        assert_eq!(
+            scanner.scan(),
+            vec!["class", "flex", "font-bold", "italic", "underline"]
+        );
+    }
+
+    #[test]
+    fn it_should_only_scan_hidden_files_inside_hidden_sources() {
+        let (dir, source) = create_project(&[
+            ("index.html", "content-['index.html']"),
+            (".storybook/preview.js", "content-['.storybook/preview.js']"),
+            ("config/.theme.js", "content-['config/.theme.js']"),
+            ("config/theme.js", "content-['config/theme.js']"),
+            ("src/.draft.html", "content-['src/.draft.html']"),
+        ]);
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![
+                source,
+                GlobEntry {
+                    base: dir.join("config").to_string_lossy().to_string(),
+                    pattern: "**/*".to_owned(),
+                },
+            ],
+            hidden_sources: Some(vec![dir.join("config")]),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            scanner.scan(),
+            vec![
+                "content-['config/.theme.js']",
+                "content-['config/theme.js']",
+                "content-['index.html']",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_read_files_again_after_clearing_their_cache() {
+        let (dir, source) =
+            create_project(&[("a.html", "content-['aaa']"), ("b.html", "content-['bbb']")]);
+
+        // Force the exact same size and modification time for every write
+        let mtime = std::time::SystemTime::now();
+        let write = |file: &str, content: &str| {
+            fs::write(dir.join(file), content).unwrap();
+            fs::File::options()
+                .write(true)
+                .open(dir.join(file))
+                .unwrap()
+                .set_modified(mtime)
+                .unwrap();
+        };
+
+        write("a.html", "content-['aaa']");
+        write("b.html", "content-['bbb']");
+
+        let mut scanner = Scanner::new(Some(vec![source]));
+
+        assert_eq!(scanner.scan(), vec!["content-['aaa']", "content-['bbb']"]);
+
+        // The change can't be detected without reading the files
+        write("a.html", "content-['ccc']");
+        write("b.html", "content-['ddd']");
+        assert_eq!(scanner.scan(), vec!["content-['aaa']", "content-['bbb']"]);
+
+        scanner.clear_file_cache(&dir.join("a.html"));
+        assert_eq!(
+            scanner.scan(),
+            vec!["content-['aaa']", "content-['bbb']", "content-['ccc']"]
+        );
+
+        scanner.clear_all_file_caches();
+        assert_eq!(
+            scanner.scan(),
+            vec![
+                "content-['aaa']",
+                "content-['bbb']",
+                "content-['ccc']",
+                "content-['ddd']",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_only_return_the_explicit_globs_without_auto_detected_globs() {
+        let (dir, _) = create_project(&[
+            ("src/index.html", "content-['src/index.html']"),
+            (
+                "src/components/button.tsx",
+                "content-['src/components/button.tsx']",
+            ),
+            ("pages/about.html", "content-['pages/about.html']"),
+        ]);
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![
+                GlobEntry {
+                    base: dir.join("src").to_string_lossy().to_string(),
+                    pattern: "**/*".to_owned(),
+                },
+                GlobEntry {
+                    base: dir.join("pages").to_string_lossy().to_string(),
+                    pattern: "*.html".to_owned(),
+                },
+            ],
+            no_auto_detect_globs: true,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            scanner.scan(),
+            vec![
+                "content-['pages/about.html']",
+                "content-['src/components/button.tsx']",
+                "content-['src/index.html']",
+            ]
+        );
+
+        let globs: Vec<_> = scanner
+            .get_globs()
+            .into_iter()
+            .map(|glob| {
+                let base = path::Path::new(&glob.base).strip_prefix(&dir).unwrap();
+                format!(
+                    "{}/{}",
+                    base.to_string_lossy().replace('\\', "/"),
+                    glob.pattern
+                )
+            })
+            .collect();
+
+        assert_eq!(globs, vec!["pages/*.html", "src/**/*"]);
+    }
+
+    #[test]
+    fn it_should_skip_css_generated_by_tailwindcss() {
+        let (dir, _) = create_project(&[
+                (
+                    "dist/output.css",
+                    "/*! tailwindcss v4.0.0 | MIT License | https://tailwindcss.com */\n.content-\\[\\'dist\\/output\\.css\\'\\] { content: 'dist/output.css'; }",
+                ),
+                ("dist/theme.css", "/* content-['dist/theme.css'] */"),
+            ]);
+
+        let sources = vec![GlobEntry {
+            base: dir.to_string_lossy().to_string(),
+            pattern: "dist/*.css".to_owned(),
+        }];
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: sources.clone(),
+            ..Default::default()
+        });
+
+        let candidates = scanner.scan();
+        assert!(candidates.contains(&"content-['dist/theme.css']".to_owned()));
+        assert!(!candidates
+            .iter()
+            .any(|candidate| candidate.contains("output")));
+        assert!(scanner
+            .get_files()
+            .iter()
+            .all(|file| !file.ends_with("output.css")));
+
+        // Generated CSS can still be scanned explicitly
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources,
+            scan_generated_css: true,
+            ..Default::default()
+        });
+
+        scanner.scan();
+        assert!(scanner
+            .get_files()
+            .iter()
+            .any(|file| file.ends_with("output.css")));
+    }
+
+    #[test]
+    fn it_should_use_the_blade_pre_processor_for_blade_templates() {
+        let (paths, candidates) = scan(&[
+            (
+                "resources/views/card.blade.php",
+                "<div @class(['p-4' => $padded])>{{ $foo }}</div>",
+            ),
+            ("public/index.php", "<?php $bar = 'underline'; ?>"),
+        ]);
+
+        assert!(paths.contains(&"resources/views/card.blade.php".to_owned()));
+        assert!(candidates.contains(&"p-4".to_owned()));
+        assert!(candidates.contains(&"underline".to_owned()));
+        assert!(!candidates.iter().any(|candidate| candidate.contains("foo")));
+        assert!(!candidates.iter().any(|candidate| candidate.contains("bar")));
+    }
+
+    #[test]
+    fn it_should_merge_candidates_that_only_differ_in_casing() {
+        let content = vec![
+            ChangedContent::Content("text-red-500 bg-[#fff]".into(), "html".into()),
+            ChangedContent::Content("text-Red-500 bg-[#FFF]".into(), "html".into()),
+        ];
+
+        let mut candidates = Scanner::new(None).scan_content(content.clone());
+        candidates.sort();
+
+        assert_eq!(
+            candidates,
+            vec!["bg-[#FFF]", "bg-[#fff]", "text-Red-500", "text-red-500"]
+        );
+
+        let mut candidates = Scanner::with_options(ScanOptions {
+            lowercase_candidates: true,
+            ..Default::default()
+        })
+        .scan_content(content);
+        candidates.sort();
+
+        assert_eq!(candidates, vec!["bg-[#fff]", "text-red-500"]);
+    }
+
+    #[test]
+    fn it_should_apply_a_batch_of_file_events() {
+        let (dir, source) = create_project(&[
+            ("a.html", "content-['a.html'] flex"),
+            ("b.html", "content-['b.html'] flex underline"),
+            ("c.html", "content-['c.html'] italic"),
+        ]);
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![source],
+            provenance: true,
+            ..Default::default()
+        });
+
+        scanner.scan();
+
+        create_files_in(
+            &dir,
+            &[
+                ("d.html", "content-['d.html'] font-bold"),
+                ("b.html", "content-['b.html'] flex"),
+            ],
+        );
+        fs::remove_file(dir.join("c.html")).unwrap();
+
+        let delta = scanner.apply_changes(
+            vec![dir.join("d.html")],
+            vec![dir.join("b.html")],
+            vec![dir.join("c.html")],
+        );
+
+        assert_eq!(
+            delta,
+            ScanDelta {
+                added: vec!["content-['d.html']".into(), "font-bold".into()],
+                removed: vec![
+                    "content-['c.html']".into(),
+                    "italic".into(),
+                    "underline".into(),
+                ],
+            }
+        );
+
+        assert_eq!(
+            scanner.scan(),
+            vec![
+                "content-['a.html']",
+                "content-['b.html']",
+                "content-['d.html']",
+                "flex",
+                "font-bold",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_skip_file_events_for_files_outside_of_the_sources() {
+        let (dir, source) = create_project(&[
+            (".gitignore", "dist/\nignored.html"),
+            ("a.html", "content-['a.html']"),
+            ("dist/.gitkeep", ""),
+        ]);
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![source],
+            excluded_extensions: vec!["md".to_owned()],
+            ..Default::default()
+        });
+
+        scanner.scan();
+
+        create_files_in(
+            &dir,
+            &[
+                ("ignored.html", "content-['ignored.html']"),
+                ("dist/b.html", "content-['dist/b.html']"),
+                ("notes.md", "content-['notes.md']"),
+                ("c.html", "content-['c.html']"),
+            ],
+        );
+
+        let delta = scanner.apply_changes(
+            vec![
+                dir.join("ignored.html"),
+                dir.join("dist/b.html"),
+                dir.join("notes.md"),
+                dir.join("c.html"),
+            ],
+            vec![],
+            vec![],
+        );
+
+        assert_eq!(delta.added, vec!["content-['c.html']"]);
+        assert_eq!(
+            scanner.scan(),
+            vec!["content-['a.html']", "content-['c.html']"]
+        );
+    }
+
+    #[test]
+    fn it_should_resume_an_interrupted_scan() {
+        let (_, source) = create_project(&[
+            ("index.html", "content-['index.html']"),
+            ("a/index.html", "content-['a/index.html'] flex"),
+            ("a/b/index.html", "content-['a/b/index.html'] underline"),
+            ("c/index.html", "content-['c/index.html'] flex"),
+        ]);
+
+        let options = ScanOptions {
+            sources: vec![source],
+            ..Default::default()
+        };
+
+        let expected = Scanner::with_options(options.clone()).scan();
+
+        // Interrupt the scan after the second directory, e.g.: when the CI job is preempted
+        let mut saved = String::new();
+        let mut completed = 0;
+        let result = Scanner::with_options(options.clone()).scan_resumable(None, |token| {
+            saved = token.encode();
+            completed += 1;
+
+            if completed == 2 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+
+        assert_eq!(result, None);
+
+        // A new scanner only scans the remaining directories
+        let mut resumed = 0;
+        let result = Scanner::with_options(options).scan_resumable(
+            Some(ResumeToken::decode(&saved)),
+            |_| {
+                resumed += 1;
+                ControlFlow::Continue(())
+            },
+        );
+
+        assert_eq!(resumed, 2);
+        assert_eq!(result, Some(expected));
+    }
+
+    #[test]
+    fn it_should_only_scan_the_allowed_extensions() {
+        let (dir, source) = create_project(&[
+            ("index.html", "content-['index.html']"),
+            ("src/app.tsx", "content-['src/app.tsx']"),
+            ("src/app.js", "content-['src/app.js']"),
+            ("docs/README.md", "content-['docs/README.md']"),
+            ("content/post.md", "content-['content/post.md']"),
+        ]);
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            sources: vec![
+                source,
+                // Explicit sources are still scanned
+                GlobEntry {
+                    base: dir.join("content").to_string_lossy().to_string(),
+                    pattern: "*.md".to_owned(),
+                },
+            ],
+            only_extensions: Some(vec!["html".into(), "tsx".into()]),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            scanner.scan(),
+            vec![
+                "content-['content/post.md']",
+                "content-['index.html']",
+                "content-['src/app.tsx']",
+            ]
+        );
+
+        // New files are filtered as well
+        create_files_in(
+            &dir,
+            &[
+                ("src/button.tsx", "content-['src/button.tsx']"),
+                ("src/notes.md", "content-['src/notes.md']"),
+            ],
+        );
+
+        assert_eq!(
+            scanner.scan(),
+            vec![
+                "content-['content/post.md']",
+                "content-['index.html']",
+                "content-['src/app.tsx']",
+                "content-['src/button.tsx']",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_expose_the_pre_processed_content_of_a_file() {
+        let dir = tempdir().unwrap().into_path();
+
+        create_files_in(
+            &dir,
+            &[(
+                "App.vue",
+                "<script setup>\nimport Button from './Button.vue'\n</script>\n<template><div class=\"flex\"></div></template>",
+            )],
+        );
+
+        let content = preprocess_file(&dir.join("App.vue")).unwrap();
+
+        assert!(content.contains(r#"<template><div class="flex"></div></template>"#));
+        assert!(!content.contains("import"));
+        assert!(!content.contains("Button from"));
+
+        assert_eq!(preprocess_file(&dir.join("missing.vue")), None);
+    }
+
+    #[test]
+    fn it_should_expand_braces_across_directories() {
+        let (dir, _) = create_project(&[
+            ("a/index.html", "content-['a/index.html']"),
+            ("a/notes.md", "content-['a/notes.md']"),
+            ("b/nested/index.html", "content-['b/nested/index.html']"),
+            ("c/index.html", "content-['c/index.html']"),
+        ]);
+
+        let mut scanner = Scanner::new(Some(vec![GlobEntry {
+            base: dir.to_string_lossy().to_string(),
+            pattern: "{a,b}/**/*.html".to_owned(),
+        }]));
+
+        assert_eq!(
+            scanner.scan(),
+            vec![
+                "content-['a/index.html']",
+                "content-['b/nested/index.html']"
+            ]
+        );
+
+        // Every alternative becomes its own base
+        let base = dunce::canonicalize(&dir).unwrap();
+        let globs: Vec<_> = scanner
+            .get_globs()
+            .into_iter()
+            .map(|glob| (glob.base, glob.pattern))
+            .collect();
+
+        assert_eq!(
+            globs,
+            vec![
+                (
+                    base.join("a").to_string_lossy().to_string(),
+                    "**/*.html".to_owned()
+                ),
+                (
+                    base.join("b").to_string_lossy().to_string(),
+                    "**/*.html".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_return_candidates_with_positions_for_multiple_files() {
+        let dir = tempdir().unwrap().into_path();
+
+        create_files_in(
+            &dir,
+            &[
+                ("a.html", r#"<div class="flex underline"></div>"#),
+                ("b.html", r#"<div class="group-[]:flex p-4"></div>"#),
+            ],
+        );
+
+        let mut scanner = Scanner::new(None);
+        let result = scanner.get_candidates_with_positions_batch(vec![
+            ChangedContent::File(dir.join("a.html"), "html".into()),
+            ChangedContent::File(dir.join("b.html"), "html".into()),
+        ]);
+
+        assert_eq!(
+            result,
+            vec![
+                (
+                    dir.join("a.html"),
+                    vec![
+                        ("class".to_string(), 5),
+                        ("flex".to_string(), 12),
+                        ("underline".to_string(), 17),
+                    ]
+                ),
+                (
+                    dir.join("b.html"),
+                    vec![
+                        ("class".to_string(), 5),
+                        ("group-[]:flex".to_string(), 12),
+                        ("p-4".to_string(), 26),
+                    ]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_use_a_known_git_root() {
+        let dir = tempdir().unwrap().into_path();
+
+        create_files_in(
+            &dir,
+            &[
+                // Not part of the repository, so this file is never used
+                (".gitignore", "outer.html"),
+                ("repo/.gitignore", "ignored.html"),
+                ("repo/index.html", "content-['index.html']"),
+                ("repo/outer.html", "content-['outer.html']"),
+                ("repo/ignored.html", "content-['ignored.html']"),
+            ],
+        );
+
+        let _ = Command::new("git")
+            .arg("init")
+            .current_dir(dir.join("repo"))
+            .output();
+
+        let scanner_with_git_root = |git_root: Option<path::PathBuf>| {
+            Scanner::with_options(ScanOptions {
+                sources: vec![GlobEntry {
+                    base: dir.join("repo").to_string_lossy().to_string(),
+                    pattern: "**/*".to_owned(),
+                }],
+                git_root,
+                ..Default::default()
+            })
+        };
+
+        let expected = vec!["content-['index.html']", "content-['outer.html']"];
+
+        // Auto-detected
+        let mut scanner = scanner_with_git_root(None);
+        assert_eq!(scanner.scan(), expected);
+
+        // Known up front
+        let mut scanner = scanner_with_git_root(Some(dir.join("repo")));
+        assert_eq!(scanner.scan(), expected);
+    }
+
+    #[test]
+    fn it_should_respect_the_repository_boundary_of_every_root() {
+        let dir = tempdir().unwrap().into_path();
+
+        create_files_in(
+            &dir,
+            &[
+                (".gitignore", "outer.html"),
+                ("repo/index.html", "content-['repo/index.html']"),
+                ("repo/outer.html", "content-['repo/outer.html']"),
+                ("plain/index.html", "content-['plain/index.html']"),
+                ("plain/outer.html", "content-['plain/outer.html']"),
+            ],
+        );
+
+        // Only one of the roots is a git repository
+        let _ = Command::new("git")
+            .arg("init")
+            .current_dir(dir.join("repo"))
+            .output();
+
+        let mut scanner = Scanner::new(Some(vec![
+            GlobEntry {
+                base: dir.join("repo").to_string_lossy().to_string(),
+                pattern: "**/*".to_owned(),
+            },
+            GlobEntry {
+                base: dir.join("plain").to_string_lossy().to_string(),
+                pattern: "**/*".to_owned(),
+            },
+        ]));
+
+        // The `.gitignore` outside of the repository only applies to the root that is not part of
+        // a repository
+        assert_eq!(
+            scanner.scan(),
+            vec![
+                "content-['plain/index.html']",
+                "content-['repo/index.html']",
+                "content-['repo/outer.html']",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_count_how_often_candidates_occur() {
+        let mut scanner = Scanner::new(None);
+
+        let counts = scanner.scan_content_with_counts(vec![
+            ChangedContent::Content(
+                "<div class=\"flex p-4\"></div>\n<span class=\"flex\"></span>".into(),
+                "html".into(),
+            ),
+            ChangedContent::Content("<div class=\"flex underline\"></div>".into(), "html".into()),
+        ]);
+
+        assert_eq!(
+            counts,
+            vec![
+                ("class".to_owned(), 3),
+                ("flex".to_owned(), 3),
+                ("p-4".to_owned(), 1),
+                ("underline".to_owned(), 1),
+            ]
+        );
+
+        // The candidates are known to the scanner afterwards
+        assert_eq!(scanner.scan(), vec!["class", "flex", "p-4", "underline"]);
+    }
+
+    #[test]
+    fn it_should_track_the_sources_of_counted_candidates() {
+        let (dir, _) = create_project(&[
+            (
+                "a.html",
+                "<div class=\"flex p-4\"></div>\n<span class=\"flex\"></span>",
+            ),
+            ("b.html", "<div class=\"flex underline\"></div>"),
+        ]);
+
+        let mut scanner = Scanner::with_options(ScanOptions {
+            provenance: true,
+            ..Default::default()
+        });
+
+        let counts = scanner.scan_content_with_counts(vec![
+            ChangedContent::File(dir.join("a.html"), "html".into()),
+            ChangedContent::File(dir.join("b.html"), "html".into()),
+        ]);
+
+        assert_eq!(
+            counts,
+            vec![
+                ("class".to_owned(), 3),
+                ("flex".to_owned(), 3),
+                ("p-4".to_owned(), 1),
+                ("underline".to_owned(), 1),
+            ]
+        );
+
+        assert_eq!(
+            scanner.source_for_candidate("flex"),
+            vec![dir.join("a.html"), dir.join("b.html")]
+        );
+        assert_eq!(
+            scanner.source_for_candidate("underline"),
+            vec![dir.join("b.html")]
+        );
+    }
+
+    #[test]
+    fn it_should_read_files_with_a_custom_content_reader() {
+        #[derive(Debug)]
+        struct StripSourceMaps;
+
+        impl ContentReader for StripSourceMaps {
+            fn read(&self, path: &path::Path) -> std::io::Result<Vec<u8>> {
+                let content = fs::read_to_string(path)?;
+                let content = match content.find("//# sourceMappingURL=") {
+                    Some(idx) => &content[..idx],
+                    None => &content[..],
+                };
+
+                Ok(content.as_bytes().to_vec())
+            }
+        }
+
+        let (_, source) = create_project(&[(
+            "app.bundle",
+            "const classes = 'flex underline'\n//# sourceMappingURL=app.bundle.map",
+        )]);
+
+        // The trailer is scanned by default
+        let mut scanner = Scanner::new(Some(vec![source.clone()]));
+        assert!(scanner.scan().contains(&"sourceMappingURL".to_owned()));
+
+        let mut scanner = Scanner::new(Some(vec![source]));
+        scanner.set_content_reader(StripSourceMaps);
+        assert_eq!(
+            scanner.scan(),
+            vec!["classes", "const", "flex", "underline"]
+        );
+    }
 }
@@ -3396,6 +3396,82 @@ describe('@source', () => {
       expect(build(['bg-red-500', 'bg-red-700'])).toMatchInlineSnapshot(`""`)
     })
   })
+
+  describe('modifiers', () => {
+    test('`not` can be combined with `inline(…)`', async () => {
+      let { build, globs } = await compile(
+        css`
+          @source not inline("underline");
+          @tailwind utilities;
+        `,
+        { base: '/root' },
+      )
+
+      expect(globs).toEqual([])
+      expect(build(['underline'])).toMatchInlineSnapshot(`""`)
+    })
+
+    test('`inline(…)` does not register a glob', async () => {
+      let { globs } = await compile(
+        css`
+          @source inline("underline");
+        `,
+        { base: '/root' },
+      )
+
+      expect(globs).toEqual([])
+    })
+
+    test('`not` is ignored for paths outside of strict mode', async () => {
+      let { globs } = await compile(
+        css`
+          @source not "./foo/**/*.ts";
+        `,
+        { base: '/root' },
+      )
+
+      expect(globs).toEqual([{ base: '/root', pattern: './foo/**/*.ts' }])
+    })
+
+    test('`not` cannot be used with a path in strict mode', () => {
+      return expect(
+        compile(
+          css`
+            @source not "./foo/**/*.ts";
+          `,
+          { base: '/root', strictSources: true },
+        ),
+      ).rejects.toThrowErrorMatchingInlineSnapshot(
+        `[Error: \`@source not\` can only be used with \`inline(…)\`.]`,
+      )
+    })
+
+    test('`external(…)` is not a known modifier in strict mode', () => {
+      return expect(
+        compile(
+          css`
+            @source external("./foo/**/*.ts");
+          `,
+          { base: '/root', strictSources: true },
+        ),
+      ).rejects.toThrowErrorMatchingInlineSnapshot(
+        `[Error: \`@source\` does not support the \`external\` modifier.]`,
+      )
+    })
+
+    test('unknown modifiers are not treated as part of the path in strict mode', () => {
+      return expect(
+        compile(
+          css`
+            @source nott "./foo/**/*.ts";
+          `,
+          { base: '/root', strictSources: true },
+        ),
+      ).rejects.toThrowErrorMatchingInlineSnapshot(
+        `[Error: \`@source\` does not support the \`nott\` modifier.]`,
+      )
+    })
+  })
 })
 
 describe('@custom-variant', () => {
@@ -45,6 +45,8 @@ type CompileOptions = {
     resourceHint: 'plugin' | 'config',
   ) => Promise<{ module: Plugin | Config; base: string }>
   loadStylesheet?: (id: string, base: string) => Promise<{ content: string; base: string }>
+  // Error on unknown `@source` modifiers, and on `@source not` without `inline(…)`
+  strictSources?: boolean
 }
 
 function throwOnLoadModule(): never {
@@ -114,6 +116,7 @@ async function parseCss(
     base = '',
     loadModule = throwOnLoadModule,
     loadStylesheet = throwOnLoadStylesheet,
+    strictSources = false,
   }: CompileOptions = {},
 ) {
   let features = Features.None
@@ -226,6 +229,17 @@ async function parseCss(
           inline = true
           path = path.slice(7, -1)
         }
+
+        if (strictSources && not && !inline) {
+          throw new Error('`@source not` can only be used with `inline(…)`.')
+        }
+      }
+
+      if (strictSources && path[0] !== '"' && path[0] !== "'") {
+        let modifier = /^[a-z-]+(?=[\s(])/.exec(path)?.[0]
+        if (modifier) {
+          throw new Error(`\`@source\` does not support the \`${modifier}\` modifier.`)
+        }
       }
 
       if (